@@ -1,7 +1,7 @@
 {
   description = "Advent of code 2020";
 
-  inputs.nixpkgs.url = "nixpkgs/nixos-20.09";
+  inputs.nixpkgs.url = "nixpkgs/nixos-25.11";
   inputs.import-cargo.url = "github:edolstra/import-cargo";
 
   outputs = { self, nixpkgs, import-cargo }:
@@ -25,6 +25,8 @@
             clippy
             rustfmt
           ] else [
+            curl
+            makeWrapper
             (import-cargo.builders.importCargo {
               lockFile = ./Cargo.lock;
               inherit pkgs;
@@ -44,6 +46,8 @@
             mkdir -p $out
             cargo install --frozen --offline --path . --root $out
             rm $out/.crates.toml
+            # https:// inputs are downloaded with curl
+            wrapProgram $out/bin/aoc2020 --prefix PATH : ${curl}/bin
           '';
       };
 
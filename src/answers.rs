@@ -0,0 +1,299 @@
+//! Expected answers of the puzzles, recorded in a plain text or TOML file
+//!
+//! The plain text format has one answer per line: the day, the part and the answer separated by
+//! spaces.  The TOML format has one table per day with a key per part:
+//!
+//! ```toml
+//! [day21]
+//! part1 = 5
+//! part2 = "mxmxvkd,sqjhc,fvjkl"
+//! ```
+
+use crate::error::number;
+use crate::{Answer, ParseError};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// The layout of an answers file
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Toml,
+}
+
+impl Format {
+    /// TOML for `.toml` files, plain text otherwise
+    pub fn of(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext == "toml" => Format::Toml,
+            _ => Format::Text,
+        }
+    }
+}
+
+/// The outcome of checking an answer against the expected one
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    /// The expected answer differs
+    Fail(Answer),
+    /// No answer was recorded
+    Missing,
+}
+
+/// The answers of the puzzles by day and part
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Answers(BTreeMap<(u8, u8), Answer>);
+
+impl Answers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.0.get(&(day, part))
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, answer: Answer) {
+        self.0.insert((day, part), answer);
+    }
+
+    /// Compare an answer with the expected one.  Answers are equal if they print the same, the
+    /// text format doesn't tell numbers and digit strings apart.
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected.to_string() == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected.clone()),
+            None => Verdict::Missing,
+        }
+    }
+
+    pub fn parse(text: &str, format: Format) -> Result<Self, ParseError> {
+        match format {
+            Format::Text => parse_text(text),
+            Format::Toml => parse_toml(text),
+        }
+    }
+
+    pub fn format(&self, format: Format) -> String {
+        let mut out = String::new();
+        let mut last_day = None;
+
+        for (&(day, part), answer) in &self.0 {
+            match format {
+                Format::Text => writeln!(out, "{} {} {}", day, part, answer),
+                Format::Toml => {
+                    if last_day.replace(day) != Some(day) {
+                        if !out.is_empty() {
+                            out.push('\n');
+                        }
+                        writeln!(out, "[day{:02}]", day).unwrap();
+                    }
+                    match answer {
+                        Answer::Integer(n) => writeln!(out, "part{} = {}", part, n),
+                        Answer::Text(s) => writeln!(out, "part{} = {:?}", part, s),
+                    }
+                }
+            }
+            .unwrap();
+        }
+
+        out
+    }
+
+    /// Read the answers from a file in the format given by its extension
+    pub fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&text, Format::of(path))
+            .map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    /// Write the answers to a file in the format given by its extension
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.format(Format::of(path)))
+            .map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+}
+
+fn answer(text: &str) -> Answer {
+    match text.parse() {
+        Ok(n) => Answer::Integer(n),
+        Err(_) => Answer::Text(text.to_string()),
+    }
+}
+
+pub(crate) fn is_blank(line: &str) -> bool {
+    line.is_empty() || line.starts_with('#')
+}
+
+fn parse_text(text: &str) -> Result<Answers, ParseError> {
+    let mut answers = Answers::new();
+
+    for line in text.lines().map(|line| line.trim()) {
+        if is_blank(line) {
+            continue;
+        }
+
+        let mut fields = line.splitn(3, ' ').map(|field| field.trim());
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(day), Some(part), Some(value)) if !value.is_empty() => {
+                answers.insert(number(text, day)?, number(text, part)?, answer(value))
+            }
+            _ => {
+                return Err(ParseError::at(
+                    text,
+                    line,
+                    "expected \"<day> <part> <answer>\"",
+                ))
+            }
+        }
+    }
+
+    Ok(answers)
+}
+
+fn parse_toml(text: &str) -> Result<Answers, ParseError> {
+    let mut answers = Answers::new();
+    let mut day = None;
+
+    for line in text.lines().map(|line| line.trim()) {
+        if is_blank(line) {
+            continue;
+        }
+
+        if let Some(table) = line.strip_prefix('[') {
+            let name = table
+                .strip_suffix(']')
+                .and_then(|name| name.strip_prefix("day"))
+                .ok_or_else(|| ParseError::at(text, line, "expected \"[dayNN]\""))?;
+            day = Some(number(text, name)?);
+            continue;
+        }
+
+        let day =
+            day.ok_or_else(|| ParseError::at(text, line, "answer outside a [dayNN] table"))?;
+        let (part, value) = part_answer(text, line)?;
+        answers.insert(day, part, value);
+    }
+
+    Ok(answers)
+}
+
+/// A `partN = <answer>` line of `text`, the answer a number or a quoted string
+pub(crate) fn part_answer(text: &str, line: &str) -> Result<(u8, Answer), ParseError> {
+    let (key, value) = line
+        .split_once('=')
+        .ok_or_else(|| ParseError::at(text, line, "expected \"partN = <answer>\""))?;
+    let (key, value) = (key.trim(), value.trim());
+
+    let part = key
+        .strip_prefix("part")
+        .ok_or_else(|| ParseError::at(text, key, "expected \"partN\""))?;
+    let part = number(text, part)?;
+
+    let value = if let Some(quoted) = value.strip_prefix('"') {
+        let s = quoted
+            .strip_suffix('"')
+            .ok_or_else(|| ParseError::at_end(line, "unterminated string").within(text, line))?;
+        Answer::Text(s.replace("\\\"", "\"").replace("\\\\", "\\"))
+    } else {
+        Answer::Integer(number(text, value)?)
+    };
+
+    Ok((part, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Answers {
+        let mut answers = Answers::new();
+        answers.insert(7, 1, Answer::from(4));
+        answers.insert(7, 2, Answer::from(32));
+        answers.insert(21, 2, Answer::from("mxmxvkd,sqjhc,fvjkl"));
+        answers.insert(23, 1, Answer::from("67384529"));
+        answers
+    }
+
+    #[test]
+    fn detects_format() {
+        assert_eq!(Format::of(Path::new("answers.toml")), Format::Toml);
+        assert_eq!(Format::of(Path::new("answers.txt")), Format::Text);
+        assert_eq!(Format::of(Path::new("answers")), Format::Text);
+    }
+
+    #[test]
+    fn parses_text() {
+        let text = "# day part answer\n7 1 4\n7 2 32\n\n21 2 mxmxvkd,sqjhc,fvjkl\n23 1 67384529\n";
+        let answers = Answers::parse(text, Format::Text).unwrap();
+        assert_eq!(answers.get(7, 2), Some(&Answer::from(32)));
+        assert_eq!(
+            answers.get(21, 2),
+            Some(&Answer::from("mxmxvkd,sqjhc,fvjkl"))
+        );
+        assert_eq!(answers.get(1, 1), None);
+
+        assert_eq!(
+            Answers::parse("7 1 4\n7 2\n", Format::Text),
+            Err(ParseError::new(2, 1, "expected \"<day> <part> <answer>\""))
+        );
+    }
+
+    #[test]
+    fn parses_toml() {
+        let text = "\
+            [day07]
+            part1 = 4
+            part2 = 32
+
+            [day21]
+            part2 = \"mxmxvkd,sqjhc,fvjkl\"
+
+            [day23]
+            part1 = \"67384529\"
+        ";
+        assert_eq!(Answers::parse(text, Format::Toml), Ok(example()));
+
+        assert_eq!(
+            Answers::parse("part1 = 4", Format::Toml),
+            Err(ParseError::new(1, 1, "answer outside a [dayNN] table"))
+        );
+        assert_eq!(
+            Answers::parse("[day01]\npart1 = \"abc", Format::Toml),
+            Err(ParseError::new(2, 13, "unterminated string"))
+        );
+    }
+
+    #[test]
+    fn formats_answers() {
+        for &format in &[Format::Text, Format::Toml] {
+            let text = example().format(format);
+            let answers = Answers::parse(&text, format).unwrap();
+            for &(day, part) in &[(7, 1), (7, 2), (21, 2), (23, 1)] {
+                let answer = example().get(day, part).unwrap().clone();
+                assert_eq!(answers.check(day, part, &answer), Verdict::Pass);
+            }
+        }
+
+        assert!(example()
+            .format(Format::Toml)
+            .starts_with("[day07]\npart1 = 4\n"));
+        assert!(example().format(Format::Text).ends_with("23 1 67384529\n"));
+    }
+
+    #[test]
+    fn checks_answers() {
+        let answers = example();
+        assert_eq!(answers.check(7, 1, &Answer::from(4)), Verdict::Pass);
+        assert_eq!(answers.check(23, 1, &Answer::from(67384529)), Verdict::Pass);
+        assert_eq!(
+            answers.check(7, 2, &Answer::from(33)),
+            Verdict::Fail(Answer::from(32))
+        );
+        assert_eq!(answers.check(8, 1, &Answer::from(5)), Verdict::Missing);
+    }
+}
@@ -0,0 +1,424 @@
+//! Cellular automata: cells live or die together, depending on their living neighbours
+//!
+//! An automaton is put together from three pieces:
+//!
+//! - the `Topology`, the space of the cells: a bounded `SquareGrid`, an N-dimensional
+//!   `Lattice` or a `HexGrid`;
+//! - the `Neighbourhood`, which cells are counted as neighbours: the `Adjacent` ones or the
+//!   first ones in `LineOfSight`;
+//! - the `Rule`, the numbers of living neighbours for which a dead cell comes alive and a
+//!   living cell stays alive.
+
+use crate::error::only_chars;
+use crate::grid::{Grid, NEIGHBOURS};
+use crate::ParseError;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// The space of the cells
+pub trait Topology {
+    /// The position of a cell
+    type Cell: Clone + Eq + Hash;
+    /// A step from a cell to an adjacent one
+    type Direction;
+
+    /// The directions to the adjacent cells, the reverse of every direction included
+    fn directions(&self) -> &[Self::Direction];
+
+    /// The cell next to `cell` in `direction`, `None` outside the space
+    fn next(&self, cell: &Self::Cell, direction: &Self::Direction) -> Option<Self::Cell>;
+
+    /// Whether the cell can come alive at all, like a seat and unlike the floor
+    fn is_site(&self, _cell: &Self::Cell) -> bool {
+        true
+    }
+
+    /// Every site of a bounded space, `None` if the space is unbounded.  In an unbounded space
+    /// only the neighbours of living cells can come alive.
+    fn sites(&self) -> Option<Vec<Self::Cell>> {
+        None
+    }
+}
+
+/// Which cells count as the neighbours of a cell
+pub trait Neighbourhood<T: Topology> {
+    fn neighbours(&self, space: &T, cell: &T::Cell) -> Vec<T::Cell>;
+}
+
+/// The cells next to the cell in every direction
+pub struct Adjacent;
+
+impl<T: Topology> Neighbourhood<T> for Adjacent {
+    fn neighbours(&self, space: &T, cell: &T::Cell) -> Vec<T::Cell> {
+        space
+            .directions()
+            .iter()
+            .filter_map(|d| space.next(cell, d))
+            .collect()
+    }
+}
+
+/// The first site seen from the cell in every direction.  Every line must end in a site or
+/// at the edge of the space.
+pub struct LineOfSight;
+
+impl<T: Topology> Neighbourhood<T> for LineOfSight {
+    fn neighbours(&self, space: &T, cell: &T::Cell) -> Vec<T::Cell> {
+        space
+            .directions()
+            .iter()
+            .filter_map(|d| {
+                let mut cell = space.next(cell, d)?;
+                while !space.is_site(&cell) {
+                    cell = space.next(&cell, d)?;
+                }
+                Some(cell)
+            })
+            .collect()
+    }
+}
+
+/// The numbers of living neighbours for which a dead cell is born and a living cell survives
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+}
+
+impl Rule {
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        Self {
+            birth: birth.to_vec(),
+            survival: survival.to_vec(),
+        }
+    }
+
+    /// Whether the cell lives in the next generation
+    pub fn alive(&self, alive: bool, neighbours: usize) -> bool {
+        if alive {
+            self.survival.contains(&neighbours)
+        } else {
+            self.birth.contains(&neighbours)
+        }
+    }
+}
+
+/// A rule in the usual `B3/S23` notation, the birth then the survival counts as digits
+impl FromStr for Rule {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let counts = |prefix: &str, part: &str| {
+            let digits = part
+                .strip_prefix(prefix)
+                .ok_or_else(|| ParseError::at(text, part, format!("expected {:?}", prefix)))?;
+            only_chars(text, digits, "012345678")?;
+            Ok(digits
+                .chars()
+                .filter_map(|c| c.to_digit(10))
+                .map(|n| n as usize)
+                .collect())
+        };
+
+        let (birth, survival) = text
+            .split_once('/')
+            .ok_or_else(|| ParseError::at(text, text, "expected \"B<counts>/S<counts>\""))?;
+
+        Ok(Self {
+            birth: counts("B", birth)?,
+            survival: counts("S", survival)?,
+        })
+    }
+}
+
+/// The living cells of a space, changing generation by generation
+pub struct Automaton<T: Topology, N> {
+    space: T,
+    neighbourhood: N,
+    rule: Rule,
+    alive: HashSet<T::Cell>,
+    previous: HashSet<T::Cell>,
+    generation: usize,
+}
+
+impl<T: Topology, N: Neighbourhood<T>> Automaton<T, N> {
+    pub fn new(
+        space: T,
+        neighbourhood: N,
+        rule: Rule,
+        alive: impl IntoIterator<Item = T::Cell>,
+    ) -> Self {
+        let alive: HashSet<T::Cell> = alive.into_iter().collect();
+        Self {
+            space,
+            neighbourhood,
+            rule,
+            previous: alive.clone(),
+            alive,
+            generation: 0,
+        }
+    }
+
+    pub fn space(&self) -> &T {
+        &self.space
+    }
+
+    pub fn alive(&self) -> &HashSet<T::Cell> {
+        &self.alive
+    }
+
+    /// The living cells before the last step
+    pub fn previous(&self) -> &HashSet<T::Cell> {
+        &self.previous
+    }
+
+    pub fn population(&self) -> usize {
+        self.alive.len()
+    }
+
+    /// The number of steps taken
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Advance by one generation, returning whether any cell changed
+    pub fn step(&mut self) -> bool {
+        // neighbourhoods are symmetric, the living cells are the neighbours of their neighbours
+        let mut counts: HashMap<T::Cell, usize> = HashMap::new();
+        for cell in &self.alive {
+            for neighbour in self.neighbourhood.neighbours(&self.space, cell) {
+                *counts.entry(neighbour).or_insert(0) += 1;
+            }
+        }
+
+        let candidates = match self.space.sites() {
+            Some(sites) => sites,
+            None => self.alive.iter().chain(counts.keys()).cloned().collect(),
+        };
+
+        let next: HashSet<T::Cell> = candidates
+            .into_iter()
+            .filter(|cell| self.space.is_site(cell))
+            .filter(|cell| {
+                let neighbours = counts.get(cell).copied().unwrap_or(0);
+                self.rule.alive(self.alive.contains(cell), neighbours)
+            })
+            .collect();
+
+        let changed = next != self.alive;
+        self.previous = std::mem::replace(&mut self.alive, next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Advance by `generations`, calling `on_step` after every step
+    pub fn run(&mut self, generations: usize, mut on_step: impl FnMut(&Self)) {
+        for _ in 0..generations {
+            self.step();
+            on_step(self);
+        }
+    }
+
+    /// Advance until no cell changes, calling `on_step` after every step including the last.
+    /// Returns `false` if the cells come back to an earlier generation instead, as they would
+    /// then cycle forever.
+    pub fn settle(&mut self, mut on_step: impl FnMut(&Self)) -> bool {
+        let mut seen = vec![self.alive.clone()];
+        loop {
+            let changed = self.step();
+            on_step(self);
+            if !changed {
+                return true;
+            }
+            if seen.contains(&self.alive) {
+                return false;
+            }
+            seen.push(self.alive.clone());
+        }
+    }
+}
+
+/// A bounded square grid of sites, every cell has eight adjacent ones
+pub struct SquareGrid {
+    sites: Grid<bool>,
+}
+
+impl SquareGrid {
+    pub fn new(sites: Grid<bool>) -> Self {
+        Self { sites }
+    }
+}
+
+impl Topology for SquareGrid {
+    type Cell = (usize, usize);
+    type Direction = (isize, isize);
+
+    fn directions(&self) -> &[Self::Direction] {
+        &NEIGHBOURS
+    }
+
+    fn next(&self, &(col, row): &Self::Cell, &(dc, dr): &Self::Direction) -> Option<Self::Cell> {
+        let (col, row) = (col as isize + dc, row as isize + dr);
+        self.sites
+            .get(col, row)
+            .map(|_| (col as usize, row as usize))
+    }
+
+    fn is_site(&self, &cell: &Self::Cell) -> bool {
+        self.sites[cell]
+    }
+
+    fn sites(&self) -> Option<Vec<Self::Cell>> {
+        Some(self.sites.positions().filter(|&p| self.sites[p]).collect())
+    }
+}
+
+/// An unbounded lattice of integer points, every point has `3^dim - 1` adjacent ones
+pub struct Lattice {
+    directions: Vec<Vec<i32>>,
+}
+
+impl Lattice {
+    pub fn new(dim: usize) -> Self {
+        let directions = (0..dim)
+            .map(|_| -1..=1)
+            .multi_cartesian_product()
+            .filter(|d| d.iter().any(|&x| x != 0))
+            .collect();
+        Self { directions }
+    }
+}
+
+impl Topology for Lattice {
+    type Cell = Vec<i32>;
+    type Direction = Vec<i32>;
+
+    fn directions(&self) -> &[Self::Direction] {
+        &self.directions
+    }
+
+    fn next(&self, cell: &Self::Cell, direction: &Self::Direction) -> Option<Self::Cell> {
+        Some(cell.iter().zip(direction).map(|(x, d)| x + d).collect())
+    }
+}
+
+/// The directions e, se, sw, w, nw and ne in axial coordinates: `x` grows to the east, `y` to
+/// the north-east
+const HEX_DIRECTIONS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+/// An unbounded grid of hexagons, every hexagon has six adjacent ones
+pub struct HexGrid;
+
+impl Topology for HexGrid {
+    type Cell = (i32, i32);
+    type Direction = (i32, i32);
+
+    fn directions(&self) -> &[Self::Direction] {
+        &HEX_DIRECTIONS
+    }
+
+    fn next(&self, &(x, y): &Self::Cell, &(dx, dy): &Self::Direction) -> Option<Self::Cell> {
+        Some((x + dx, y + dy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life() -> Rule {
+        Rule::new(&[3], &[2, 3])
+    }
+
+    #[test]
+    fn parses_rules() {
+        assert_eq!("B3/S23".parse(), Ok(life()));
+        assert_eq!("B0/S0123".parse(), Ok(Rule::new(&[0], &[0, 1, 2, 3])));
+        assert_eq!("B/S".parse(), Ok(Rule::new(&[], &[])));
+        assert_eq!(
+            "B3/S2x".parse::<Rule>(),
+            Err(ParseError::new(1, 6, "unexpected character 'x'"))
+        );
+        assert_eq!(
+            "B3/23".parse::<Rule>(),
+            Err(ParseError::new(1, 4, "expected \"S\""))
+        );
+        assert_eq!(
+            "B3".parse::<Rule>(),
+            Err(ParseError::new(1, 1, "expected \"B<counts>/S<counts>\""))
+        );
+    }
+
+    #[test]
+    fn applies_rules() {
+        let rule = life();
+        assert!(rule.alive(false, 3));
+        assert!(!rule.alive(false, 2));
+        assert!(rule.alive(true, 2));
+        assert!(!rule.alive(true, 4));
+    }
+
+    #[test]
+    fn finds_neighbours() {
+        assert_eq!(Lattice::new(3).directions().len(), 26);
+        assert_eq!(Lattice::new(4).directions().len(), 80);
+
+        let mut hexagons = Adjacent.neighbours(&HexGrid, &(0, 0));
+        hexagons.sort();
+        assert_eq!(
+            hexagons,
+            [(-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0)]
+        );
+
+        let seats = Grid::parse("L..L\n....\nL.L.", "L.").unwrap();
+        let space = SquareGrid::new(seats.map(|&c| c == 'L'));
+        assert_eq!(Adjacent.neighbours(&space, &(0, 0)).len(), 3);
+        let mut visible = LineOfSight.neighbours(&space, &(0, 0));
+        visible.sort();
+        assert_eq!(visible, [(0, 2), (2, 2), (3, 0)]);
+    }
+
+    #[test]
+    fn runs_generations() {
+        // a blinker turns between a row and a column
+        let row = vec![vec![0, 1], vec![1, 1], vec![2, 1]];
+        let mut blinker = Automaton::new(Lattice::new(2), Adjacent, life(), row.clone());
+        let mut populations = vec![];
+        blinker.run(2, |a| populations.push((a.generation(), a.population())));
+
+        assert_eq!(populations, [(1, 3), (2, 3)]);
+        assert_eq!(blinker.alive(), &row.into_iter().collect());
+        let column = vec![vec![1, 0], vec![1, 1], vec![1, 2]];
+        assert_eq!(blinker.previous(), &column.into_iter().collect());
+    }
+
+    #[test]
+    fn settles() {
+        // every seat is taken, then the crowded middle one is left
+        let seats = Grid::new(3, vec![true; 3]);
+        let mut automaton = Automaton::new(
+            SquareGrid::new(seats),
+            Adjacent,
+            Rule::new(&[0], &[0, 1]),
+            vec![],
+        );
+        let mut populations = vec![];
+        assert!(automaton.settle(|a| populations.push(a.population())));
+
+        assert_eq!(populations, [3, 2, 2]);
+        assert_eq!(automaton.generation(), 3);
+    }
+
+    #[test]
+    fn stops_settling_on_a_cycle() {
+        // the blinker is back in a row after two steps
+        let row = vec![vec![0, 1], vec![1, 1], vec![2, 1]];
+        let mut blinker = Automaton::new(Lattice::new(2), Adjacent, life(), row);
+        let mut generations = vec![];
+
+        assert!(!blinker.settle(|a| generations.push(a.generation())));
+        assert_eq!(generations, [1, 2]);
+    }
+}
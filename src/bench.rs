@@ -0,0 +1,213 @@
+//! Run time statistics of the solutions over repeated runs
+
+use crate::{json, Puzzle};
+use std::error::Error;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// The spread of the run times of one step
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// The statistics of at least one sample
+    pub fn new(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "No samples");
+
+        let mut samples = samples.to_vec();
+        samples.sort();
+
+        let n = samples.len();
+        let median = if n % 2 == 1 {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            max: samples[n - 1],
+        }
+    }
+
+    fn json(&self) -> String {
+        format!(
+            r#"{{"min_ns":{},"median_ns":{},"max_ns":{}}}"#,
+            self.min.as_nanos(),
+            self.median.as_nanos(),
+            self.max.as_nanos()
+        )
+    }
+}
+
+/// The run times of parsing the input and solving each part of a puzzle
+#[derive(Clone, Debug, PartialEq)]
+pub struct Benchmark {
+    pub day: u8,
+    pub title: &'static str,
+    pub runs: usize,
+    pub parse: Stats,
+    pub parts: Vec<(u8, Stats)>,
+}
+
+/// Parse the input and solve every part of the puzzle `runs` times
+pub fn run(puzzle: &Puzzle, input: &str, runs: usize) -> Result<Benchmark, Box<dyn Error>> {
+    if runs == 0 {
+        return Err("At least one run is needed".into());
+    }
+
+    let parts = puzzle.parts();
+    let mut parse = vec![];
+    let mut solve = vec![vec![]; parts.len()];
+
+    for _ in 0..runs {
+        let timed = puzzle.timed(input, &parts)?;
+        parse.push(timed.parse);
+        for (samples, (_, elapsed)) in solve.iter_mut().zip(timed.parts) {
+            samples.push(elapsed);
+        }
+    }
+
+    Ok(Benchmark {
+        day: puzzle.day,
+        title: puzzle.title,
+        runs,
+        parse: Stats::new(&parse),
+        parts: parts
+            .into_iter()
+            .zip(solve)
+            .map(|(part, samples)| (part, Stats::new(&samples)))
+            .collect(),
+    })
+}
+
+/// One row per step of every puzzle
+pub fn table(benchmarks: &[Benchmark]) -> String {
+    let mut out = format!(
+        "{:<3}  {:<6}  {:>12}  {:>12}  {:>12}\n",
+        "Day", "Step", "Min", "Median", "Max"
+    );
+
+    for b in benchmarks {
+        let steps = std::iter::once(("parse".to_string(), b.parse)).chain(
+            b.parts
+                .iter()
+                .map(|(part, s)| (format!("part {}", part), *s)),
+        );
+
+        for (step, stats) in steps {
+            writeln!(
+                out,
+                "{:<3}  {:<6}  {:>12}  {:>12}  {:>12}",
+                format!("{:02}", b.day),
+                step,
+                format!("{:.3?}", stats.min),
+                format!("{:.3?}", stats.median),
+                format!("{:.3?}", stats.max),
+            )
+            .unwrap();
+        }
+    }
+
+    out
+}
+
+/// A JSON array with one object per puzzle, the times in nanoseconds
+pub fn json(benchmarks: &[Benchmark]) -> String {
+    let objects: Vec<String> = benchmarks
+        .iter()
+        .map(|b| {
+            let mut object = format!(
+                r#"{{"day":{},"title":{},"runs":{},"parse":{}"#,
+                b.day,
+                json::string(b.title),
+                b.runs,
+                b.parse.json()
+            );
+            for (part, stats) in &b.parts {
+                write!(object, r#","part{}":{}"#, part, stats.json()).unwrap();
+            }
+            object.push('}');
+            object
+        })
+        .collect();
+
+    format!("[{}]", objects.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    fn example() -> Benchmark {
+        Benchmark {
+            day: 1,
+            title: "Report Repair",
+            runs: 3,
+            parse: Stats::new(&[Duration::from_micros(20)]),
+            parts: vec![(1, Stats::new(&[ms(3), ms(1), ms(2)]))],
+        }
+    }
+
+    #[test]
+    fn computes_stats() {
+        assert_eq!(
+            Stats::new(&[ms(5), ms(1), ms(3)]),
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                max: ms(5)
+            }
+        );
+        assert_eq!(Stats::new(&[ms(4), ms(1), ms(2), ms(9)]).median, ms(3));
+        assert_eq!(Stats::new(&[ms(7)]).median, ms(7));
+    }
+
+    #[test]
+    fn runs_puzzles() {
+        let b = run(puzzle(1).unwrap(), "1721\n979\n366\n299\n675\n1456\n", 3).unwrap();
+        assert_eq!((b.day, b.runs), (1, 3));
+        assert_eq!(
+            b.parts.iter().map(|&(part, _)| part).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert!(b.parse.min <= b.parse.median && b.parse.median <= b.parse.max);
+
+        assert!(run(puzzle(1).unwrap(), "1721\n", 0).is_err());
+        assert!(run(puzzle(1).unwrap(), "x\n", 1).is_err());
+    }
+
+    #[test]
+    fn formats_table() {
+        assert_eq!(
+            table(&[example()]),
+            "\
+Day  Step             Min        Median           Max
+01   parse       20.000µs      20.000µs      20.000µs
+01   part 1       1.000ms       2.000ms       3.000ms
+"
+        );
+    }
+
+    #[test]
+    fn formats_json() {
+        assert_eq!(
+            json(&[example()]),
+            concat!(
+                r#"[{"day":1,"title":"Report Repair","runs":3,"#,
+                r#""parse":{"min_ns":20000,"median_ns":20000,"max_ns":20000},"#,
+                r#""part1":{"min_ns":1000000,"median_ns":2000000,"max_ns":3000000}}]"#
+            )
+        );
+    }
+}
@@ -1,63 +1,15 @@
-use std::collections::HashSet;
+use aoc2020::{day01, input};
+use std::error::Error;
 
-const INPUT: &[i32] = &[
-    1891, 1975, 1987, 1923, 1928, 1993, 1946, 1947, 2005, 1897, 1971, 1929, 1875, 1945, 1680, 811,
-    1901, 1396, 1942, 1282, 1941, 1978, 1884, 1879, 1230, 2010, 1881, 1979, 1996, 1904, 1934, 1865,
-    2003, 2006, 1966, 1860, 1259, 1959, 1931, 1963, 1878, 1880, 151, 1925, 1663, 1908, 1863, 1391,
-    1922, 1968, 1998, 1084, 1982, 1960, 1938, 1876, 1937, 1882, 1873, 1926, 1986, 1416, 1864, 1862,
-    1969, 1913, 532, 1866, 1242, 1933, 1903, 965, 1927, 1890, 1991, 1388, 1992, 1902, 1907, 1964,
-    1394, 2009, 1920, 630, 1932, 1854, 1951, 1852, 1983, 1314, 1855, 1954, 1921, 1989, 1871, 1995,
-    1885, 1974, 1915, 1872, 1251, 1899, 1985, 1889, 1935, 1912, 946, 1965, 1739, 1973, 1911, 1910,
-    1917, 1918, 1900, 1886, 1477, 2000, 1916, 1077, 2004, 1456, 1867, 1970, 1999, 1919, 1726, 706,
-    1930, 1994, 1988, 1997, 1870, 1953, 652, 1893, 1898, 1883, 1957, 1972, 1874, 1977, 1955, 2001,
-    1906, 1389, 1848, 1940, 1877, 1962, 1948, 1887, 1924, 1403, 1408, 1861, 1892, 1990, 1222, 677,
-    1392, 1113, 1085, 1894, 1106, 1939, 1961, 1944, 1952, 1643, 1404, 1895, 1958, 1976, 1206, 1905,
-    1076, 1888, 1896, 1943, 1950, 2008, 1967, 164, 1981, 1868, 1914, 1909, 1956, 341, 1379, 2007,
-    1563, 1980, 1072, 1949, 1250, 1258, 1092, 2002,
-];
+fn main() -> Result<(), Box<dyn Error>> {
+    let file = input::from_args(1)?;
 
-fn find_common(input: &[i32], base: i32) -> HashSet<i32> {
-    let input_set: HashSet<_> = input.iter().cloned().collect();
-    let complement: HashSet<_> = input.iter().map(|x| base - x).collect();
+    let input = day01::parse(&file)?;
 
-    input_set.intersection(&complement).cloned().collect()
-}
-
-fn solve_part1(input: &[i32]) -> i32 {
-    find_common(&input, 2020).iter().product()
-}
-
-fn solve_part2(input: &[i32]) -> i32 {
-    for base in input.iter() {
-        let c = find_common(&input, 2020 - base);
-        let sum = c.iter().sum::<i32>() + base;
-        if sum == 2020 {
-            let p: i32 = c.iter().product();
-            return base * p;
-        }
-    }
-
-    panic!("This should not happen")
-}
-
-fn main() {
-    println!("Part 1 {:?}", solve_part1(&INPUT));
-    println!("Part 2 {:?}", solve_part2(&INPUT));
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &[i32] = &[1721, 979, 366, 299, 675, 1456];
-
-    #[test]
-    fn part1_example() {
-        assert_eq!(solve_part1(&EXAMPLE), 514579);
-    }
+    let part1 = day01::solve_part1(&input).ok_or("No two entries sum to 2020")?;
+    println!("Part 1 {}", part1);
+    let part2 = day01::solve_part2(&input).ok_or("No three entries sum to 2020")?;
+    println!("Part 2 {}", part2);
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(solve_part2(&EXAMPLE), 241861950);
-    }
+    Ok(())
 }
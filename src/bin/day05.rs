@@ -1,74 +1,14 @@
+use aoc2020::{day05, input};
 use std::error::Error;
-use std::fs::read_to_string;
-
-fn decode(text: &str, base: i32, _lower: &char, upper: &char) -> i32 {
-    let mut base = base;
-    let mut offset = 0;
-
-    for c in text.chars() {
-        base /= 2;
-        if c == *upper {
-            offset += base
-        }
-    }
-
-    offset
-}
-
-fn decode_row(text: &str) -> i32 {
-    decode(&text, 128, &'F', &'B')
-}
-
-fn decode_column(text: &str) -> i32 {
-    decode(&text, 8, &'L', &'R')
-}
-
-fn seat_ids(lines: &[&str]) -> Vec<i32> {
-    lines
-        .iter()
-        .map(|line| (&line[0..7], &line[7..10]))
-        .map(|(row, col)| (decode_row(&row), decode_column(&col)))
-        .map(|(row, col)| 8 * row + col)
-        .collect()
-}
-
-fn solve_part1(lines: &[&str]) -> i32 {
-    *seat_ids(lines).iter().max().unwrap()
-}
-
-fn solve_part2(lines: &[&str]) -> i32 {
-    let mut r = seat_ids(lines);
-
-    r.sort();
-
-    let mut x = r
-        .windows(2)
-        .map(|w| (w[0], w[1] - w[0]))
-        .filter(|(_, diff)| *diff == 2);
-
-    let (seat_id, _) = x.next().unwrap();
-
-    seat_id + 1
-}
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let file = read_to_string("inputs/day05.txt")?;
-    let lines: Vec<_> = file.lines().collect();
+    let file = input::from_args(5)?;
 
-    println!("Part 1 {:?}", solve_part1(&lines));
-    println!("Part 2 {:?}", solve_part2(&lines));
+    let input = day05::parse(&file)?;
 
-    Ok(())
-}
+    println!("Part 1 {}", day05::solve_part1(&input));
+    let part2 = day05::solve_part2(&input).ok_or("no free seat between two taken ones")?;
+    println!("Part 2 {}", part2);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn example_rows() {
-        assert_eq!(decode_row("BFFFBBF"), 70);
-        assert_eq!(decode_row("FFFBBBF"), 14);
-        assert_eq!(decode_row("BBFFBBF"), 102);
-    }
+    Ok(())
 }
@@ -1,53 +1,13 @@
-use std::collections::HashSet;
+use aoc2020::{day06, input};
 use std::error::Error;
-use std::fs::read_to_string;
-
-fn solve_part1(lines: &[&str]) -> usize {
-    let mut sum = 0;
-
-    for group in lines.split(|&line| line == "") {
-        let mut questions: HashSet<char> = HashSet::new();
-
-        for person in group {
-            for answer in person.chars() {
-                questions.insert(answer);
-            }
-        }
-
-        sum += questions.len();
-    }
-
-    sum
-}
-
-fn common_answers(a: &str, b: &str) -> String {
-    let a: HashSet<char> = a.chars().collect();
-    let b: HashSet<char> = b.chars().collect();
-
-    a.intersection(&b).collect()
-}
-
-fn solve_part2(lines: &[&str]) -> usize {
-    let mut sum = 0;
-
-    for group in lines.split(|&line| line == "") {
-        sum += group
-            .iter()
-            .fold(group[0].to_string(), |acc, x| {
-                common_answers(&acc, &x)
-            })
-            .len();
-    }
-
-    sum
-}
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let file = read_to_string("inputs/day06.txt")?;
-    let lines: Vec<_> = file.lines().collect();
+    let file = input::from_args(6)?;
+
+    let input = day06::parse(&file)?;
 
-    println!("Part 1 {:?}", solve_part1(&lines));
-    println!("Part 2 {:?}", solve_part2(&lines));
+    println!("Part 1 {}", day06::solve_part1(&input));
+    println!("Part 2 {}", day06::solve_part2(&input));
 
     Ok(())
 }
@@ -0,0 +1,407 @@
+//! Day 1: Report Repair
+
+use crate::error::number;
+use crate::random::Rng;
+use crate::repl::{arg, Reply};
+use crate::{Answer, ParseError, Solution};
+use itertools::iproduct;
+use std::collections::HashSet;
+
+/// The expense report, one entry per line
+pub fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+    input
+        .lines()
+        .map(|line| number(input, line.trim()))
+        .collect()
+}
+
+/// Entries of the report summing to a target
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+    /// The positions of the entries in the report, increasing
+    pub indices: Vec<usize>,
+    /// The entries in the order of their positions
+    pub entries: Vec<i32>,
+}
+
+impl Group {
+    pub fn product(&self) -> i64 {
+        self.entries.iter().map(|&x| x as i64).product()
+    }
+}
+
+/// Every group of `k` entries summing to `target`.  An entry is used at most once in a group,
+/// equal entries at different positions make different groups.
+pub fn k_sum(input: &[i32], k: usize, target: i32) -> Vec<Group> {
+    let mut groups = vec![];
+    search_groups(input, k, target, &mut |group| {
+        groups.push(group);
+        false
+    });
+    groups
+}
+
+/// Like `k_sum`, stopping at the first group found
+pub fn first_k_sum(input: &[i32], k: usize, target: i32) -> Option<Group> {
+    let mut first = None;
+    search_groups(input, k, target, &mut |group| {
+        first = Some(group);
+        true
+    });
+    first
+}
+
+/// Pass the groups of `k` entries summing to `target` to `found` until it returns true
+fn search_groups(input: &[i32], k: usize, target: i32, found: &mut dyn FnMut(Group) -> bool) {
+    // the entries by value with their positions
+    let mut sorted: Vec<(i64, usize)> = input.iter().map(|&x| x as i64).zip(0..).collect();
+    sorted.sort_unstable();
+
+    let mut chosen = Vec::with_capacity(k);
+    search(&sorted, k, target as i64, &mut chosen, &mut |positions| {
+        let mut indices = positions.to_vec();
+        indices.sort_unstable();
+        let entries = indices.iter().map(|&i| input[i]).collect();
+        found(Group { indices, entries })
+    });
+}
+
+/// Fix the smallest entries one at a time and find the last two with two pointers, O(n^(k-1))
+/// for k >= 2.  `chosen` holds the positions of the entries fixed so far.  Returns true once
+/// `found` does.
+fn search(
+    sorted: &[(i64, usize)],
+    k: usize,
+    target: i64,
+    chosen: &mut Vec<usize>,
+    found: &mut dyn FnMut(&[usize]) -> bool,
+) -> bool {
+    if k > sorted.len() {
+        return false;
+    }
+
+    match k {
+        0 => target == 0 && found(chosen),
+        1 => {
+            let start = sorted.partition_point(|&(x, _)| x < target);
+            for &(_, i) in sorted[start..].iter().take_while(|&&(x, _)| x == target) {
+                chosen.push(i);
+                let stop = found(chosen);
+                chosen.pop();
+                if stop {
+                    return true;
+                }
+            }
+            false
+        }
+        2 => {
+            let (mut lo, mut hi) = (0, sorted.len() - 1);
+            while lo < hi {
+                let sum = sorted[lo].0 + sorted[hi].0;
+                if sum < target {
+                    lo += 1;
+                } else if sum > target {
+                    hi -= 1;
+                } else {
+                    // every pair of the runs of equal values at both ends
+                    let lo_end = lo + sorted[lo..=hi].partition_point(|e| e.0 == sorted[lo].0);
+                    let hi_start = if sorted[lo].0 == sorted[hi].0 {
+                        lo
+                    } else {
+                        lo + sorted[lo..=hi].partition_point(|e| e.0 < sorted[hi].0)
+                    };
+                    for a in lo..lo_end {
+                        for b in hi_start.max(a + 1)..=hi {
+                            chosen.extend([sorted[a].1, sorted[b].1]);
+                            let stop = found(chosen);
+                            chosen.truncate(chosen.len() - 2);
+                            if stop {
+                                return true;
+                            }
+                        }
+                    }
+                    if lo_end > hi_start {
+                        break;
+                    }
+                    lo = lo_end;
+                    hi = hi_start - 1;
+                }
+            }
+            false
+        }
+        _ => {
+            for i in 0..=sorted.len() - k {
+                let (x, position) = sorted[i];
+                let rest = &sorted[i + 1..];
+                // the smallest sum with this entry is already too large
+                let smallest: i64 = rest[..k - 1].iter().map(|e| e.0).sum();
+                if x + smallest > target {
+                    break;
+                }
+                let largest: i64 = rest[rest.len() - (k - 1)..].iter().map(|e| e.0).sum();
+                if x + largest < target {
+                    continue;
+                }
+
+                chosen.push(position);
+                let stop = search(rest, k - 1, target - x, chosen, found);
+                chosen.pop();
+                if stop {
+                    return true;
+                }
+            }
+            false
+        }
+    }
+}
+
+/// The product of two entries summing to 2020, `None` if no two entries do
+pub fn solve_part1(input: &[i32]) -> Option<i64> {
+    first_k_sum(input, 2, 2020).map(|group| group.product())
+}
+
+/// The product of three entries summing to 2020, `None` if no three entries do
+pub fn solve_part2(input: &[i32]) -> Option<i64> {
+    first_k_sum(input, 3, 2020).map(|group| group.product())
+}
+
+/// Whether `x` would add a pair or a triple summing to 2020 to the entries, counting an
+/// entry more than once
+fn sums_to_2020(entries: &HashSet<i32>, x: i32) -> bool {
+    let with_x = |y| y == x || entries.contains(&y);
+    with_x(2020 - x)
+        || entries
+            .iter()
+            .chain(std::iter::once(&x))
+            .any(|&y| with_x(2020 - x - y))
+}
+
+/// An expense report of `size` entries, between 5 and 500, with exactly one pair and one
+/// triple summing to 2020
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.clamp(5, 500);
+    loop {
+        let a = rng.between(1, 1009) as i32;
+        let (b, c) = (rng.between(1, 673) as i32, rng.between(1, 673) as i32);
+        let planted = [a, 2020 - a, b, c, 2020 - b - c];
+
+        let mut entries = HashSet::new();
+        let unique = planted.iter().all(|&x| entries.insert(x));
+        let pairs = iproduct!(planted, planted)
+            .filter(|(x, y)| x + y == 2020)
+            .count();
+        let triples = iproduct!(planted, planted, planted)
+            .filter(|(x, y, z)| x + y + z == 2020)
+            .count();
+        // the pair in both orders, the triple in all six
+        if !unique || pairs != 2 || triples != 6 {
+            continue;
+        }
+
+        let mut report = planted.to_vec();
+        for _ in 0..100 * size {
+            if report.len() == size {
+                break;
+            }
+            let x = if rng.chance(0.9) {
+                rng.between(674, 2019)
+            } else {
+                rng.between(1, 673)
+            } as i32;
+            if !entries.contains(&x) && !sums_to_2020(&entries, x) {
+                entries.insert(x);
+                report.push(x);
+            }
+        }
+
+        rng.shuffle(&mut report);
+        return report.iter().map(|x| format!("{}\n", x)).collect();
+    }
+}
+
+/// The groups of `args[0]` entries summing to `args[1]`, with their lines
+fn sums(input: &[i32], args: &[&str]) -> Reply {
+    let groups = k_sum(input, arg(args, 0, "group size")?, arg(args, 1, "target")?);
+    let lines: Vec<String> = groups
+        .iter()
+        .map(|group| {
+            let entries: Vec<String> = group
+                .indices
+                .iter()
+                .zip(&group.entries)
+                .map(|(i, x)| format!("{} (line {})", x, i + 1))
+                .collect();
+            format!("{}, product {}", entries.join(" + "), group.product())
+        })
+        .collect();
+    let plural = if groups.len() == 1 { "" } else { "s" };
+    Ok(format!(
+        "{} group{}\n{}",
+        groups.len(),
+        plural,
+        lines.join("\n")
+    ))
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        solve_part1(input)
+            .map(Answer::from)
+            .ok_or_else(|| "No two entries sum to 2020".to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        solve_part2(input)
+            .map(Answer::from)
+            .ok_or_else(|| "No three entries sum to 2020".to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    const QUERIES: &'static [(&'static str, &'static str)] = &[(
+        "sums <k> <target>",
+        "The groups of <k> entries summing to <target>, with their lines",
+    )];
+
+    fn query(input: &Self::Input, query: &str, args: &[&str]) -> Option<Reply> {
+        match query {
+            "sums" => Some(sums(input, args)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    const EXAMPLE: &[i32] = &[1721, 979, 366, 299, 675, 1456];
+
+    #[test]
+    fn parses_report() {
+        assert_eq!(
+            parse("1721\n979\n366\n299\n675\n1456\n"),
+            Ok(EXAMPLE.to_vec())
+        );
+        assert_eq!(
+            parse("1721\n97x\n"),
+            Err(ParseError::new(2, 1, "invalid number \"97x\""))
+        );
+    }
+
+    #[test]
+    fn part1_example() {
+        assert_eq!(solve_part1(EXAMPLE), Some(514579));
+    }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(solve_part2(EXAMPLE), Some(241861950));
+    }
+
+    #[test]
+    fn uses_entries_once() {
+        // an entry doesn't pair with itself, but with an equal entry
+        assert_eq!(solve_part1(&[1010]), None);
+        assert_eq!(solve_part1(&[1010, 3, 1010]), Some(1010 * 1010));
+        assert_eq!(solve_part1(&[1010, 1010, 1010]), Some(1010 * 1010));
+        assert_eq!(k_sum(&[1010, 1010, 1010], 2, 2020).len(), 3);
+
+        assert_eq!(solve_part2(&[673, 674]), None);
+        assert_eq!(solve_part2(&[1000, 10]), None);
+        assert_eq!(solve_part2(&[673, 1, 674, 673]), Some(673 * 673 * 674));
+        assert_eq!(k_sum(&[673, 1, 674, 673], 3, 2020)[0].indices, [0, 2, 3]);
+    }
+
+    #[test]
+    fn finds_no_solution() {
+        assert_eq!(solve_part1(&[]), None);
+        assert_eq!(solve_part1(&[1, 2, 3]), None);
+        assert_eq!(solve_part2(&[2018, 1]), None);
+        assert_eq!(solve_part2(&[2000, 10, 11, 12]), None);
+
+        let puzzle = crate::puzzle(1).unwrap();
+        assert_eq!(
+            puzzle.solve("1\n2\n3\n", &[1]).unwrap_err().to_string(),
+            "Part 1: No two entries sum to 2020"
+        );
+        assert_eq!(
+            Day01::solve(&vec![2019, 1], 2),
+            Some(Err("No three entries sum to 2020".to_string()))
+        );
+    }
+
+    #[test]
+    fn finds_groups() {
+        let group = |indices: &[usize]| Group {
+            indices: indices.to_vec(),
+            entries: indices.iter().map(|&i| EXAMPLE[i]).collect(),
+        };
+        assert_eq!(k_sum(EXAMPLE, 2, 2020), [group(&[0, 3])]);
+        assert_eq!(first_k_sum(EXAMPLE, 3, 2020), Some(group(&[1, 2, 4])));
+        assert_eq!(k_sum(EXAMPLE, 1, 366), [group(&[2])]);
+        assert_eq!(k_sum(EXAMPLE, 0, 0), [group(&[])]);
+        assert_eq!(k_sum(EXAMPLE, 7, 5496), []);
+        assert_eq!(k_sum(EXAMPLE, 6, 5496), [group(&[0, 1, 2, 3, 4, 5])]);
+        assert_eq!(first_k_sum(EXAMPLE, 2, 1), None);
+
+        // every group of equal entries, and negative ones
+        assert_eq!(k_sum(&[1, 1, 1], 2, 2).len(), 3);
+        assert_eq!(k_sum(&[2, 1, 1, 3, 3], 2, 4).len(), 4);
+        assert_eq!(k_sum(&[-5, 10, 0, 5, -10], 3, 0).len(), 2);
+
+        assert_eq!(
+            Day01::query(&EXAMPLE.to_vec(), "sums", &["2", "2020"]),
+            Some(Ok(
+                "1 group\n1721 (line 1) + 299 (line 4), product 514579".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        let mut rng = Rng::new(3);
+        for _ in 0..200 {
+            let input: Vec<i32> = (0..rng.below(12))
+                .map(|_| rng.below(10) as i32 - 3)
+                .collect();
+            let target = rng.below(20) as i32 - 5;
+            for k in 0..=4 {
+                let mut groups: Vec<Vec<usize>> = (0..input.len())
+                    .combinations(k)
+                    .filter(|c| c.iter().map(|&i| input[i]).sum::<i32>() == target)
+                    .collect();
+                let mut found: Vec<Vec<usize>> = k_sum(&input, k, target)
+                    .into_iter()
+                    .map(|g| g.indices)
+                    .collect();
+                groups.sort();
+                found.sort();
+                assert_eq!(found, groups, "{:?} k = {} target = {}", input, k, target);
+            }
+        }
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..20 {
+            let report = parse(&generate(&mut Rng::new(seed), 200)).unwrap();
+            assert_eq!(report.len(), 200);
+            assert_eq!(k_sum(&report, 2, 2020).len(), 1);
+            assert_eq!(k_sum(&report, 3, 2020).len(), 1);
+            assert!(solve_part1(&report) > Some(0));
+            assert!(solve_part2(&report) > Some(0));
+        }
+    }
+}
@@ -0,0 +1,565 @@
+//! Day 2: Password Philosophy
+
+use crate::error::{number, parse_lines};
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use regex::Regex;
+
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub min_occurs: usize,
+    pub max_occurs: usize,
+    pub letter: char,
+    pub password: String,
+}
+
+impl Entry {
+    pub fn valid_part1(&self) -> bool {
+        CountRange.valid(self)
+    }
+
+    pub fn valid_part2(&self) -> bool {
+        PositionXor.valid(self)
+    }
+
+    /// The number of times the letter occurs in the password
+    pub fn count(&self) -> usize {
+        self.password.chars().filter(|x| *x == self.letter).count()
+    }
+
+    /// Whether the letter is at the two positions, counted from 1
+    pub fn positions(&self) -> (bool, bool) {
+        let at = |pos: usize| self.password.chars().nth(pos - 1) == Some(self.letter);
+        (at(self.min_occurs), at(self.max_occurs))
+    }
+
+    /// Which of the two positions hold the letter, in words
+    fn matched_positions(&self) -> String {
+        let (first, second) = (self.min_occurs, self.max_occurs);
+        let positions = match self.positions() {
+            (true, true) => format!("at positions {} and {}", first, second),
+            (true, false) => format!("at position {} only", first),
+            (false, true) => format!("at position {} only", second),
+            (false, false) => format!("at neither position {} nor {}", first, second),
+        };
+        format!("{:?} {}", self.letter, positions)
+    }
+
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let re = Regex::new(
+            r"(?x)
+            ^(?P<min_occurs>\d+)
+            -
+            (?P<max_occurs>\d+)
+            \s
+            (?P<letter>\S)
+            :
+            \s
+            (?P<password>\S+)
+            \s*$
+            ",
+        )
+        .expect("Invalid regular expression");
+        let captures = re.captures(input).ok_or_else(|| {
+            ParseError::at(
+                input,
+                input,
+                "expected \"<min>-<max> <letter>: <password>\"",
+            )
+        })?;
+
+        let min = captures.name("min_occurs").unwrap().as_str();
+        let max = captures.name("max_occurs").unwrap().as_str();
+        let min_occurs = number(input, min)?;
+        let max_occurs = number(input, max)?;
+        if min_occurs == 0 || max_occurs < min_occurs {
+            return Err(ParseError::at(input, min, "invalid position range"));
+        }
+        let letter = captures["letter"].chars().next().unwrap();
+        let password = captures["password"].to_string();
+
+        Ok(Self {
+            min_occurs,
+            max_occurs,
+            letter,
+            password,
+        })
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Entry>, ParseError> {
+    parse_lines(input, Entry::parse)
+}
+
+/// A rule deciding whether the password of an entry is valid
+pub trait Policy {
+    /// The name selecting the policy, with its parameters, see `policy`
+    fn name(&self) -> String;
+
+    /// Why the password of the entry is not valid, if it isn't
+    fn check(&self, entry: &Entry) -> Result<(), String>;
+
+    fn valid(&self, entry: &Entry) -> bool {
+        self.check(entry).is_ok()
+    }
+}
+
+/// The letter occurs between the two numbers of times, the policy of part 1
+pub struct CountRange;
+
+impl Policy for CountRange {
+    fn name(&self) -> String {
+        "count".to_string()
+    }
+
+    fn check(&self, entry: &Entry) -> Result<(), String> {
+        let count = entry.count();
+        if (entry.min_occurs..=entry.max_occurs).contains(&count) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} times {:?}, expected {} to {}",
+                count, entry.letter, entry.min_occurs, entry.max_occurs
+            ))
+        }
+    }
+}
+
+/// The letter is at exactly one of the two positions, the policy of part 2
+pub struct PositionXor;
+
+impl Policy for PositionXor {
+    fn name(&self) -> String {
+        "xor".to_string()
+    }
+
+    fn check(&self, entry: &Entry) -> Result<(), String> {
+        let (first, second) = entry.positions();
+        if first ^ second {
+            Ok(())
+        } else {
+            Err(entry.matched_positions())
+        }
+    }
+}
+
+/// The letter is at both positions
+pub struct PositionAnd;
+
+impl Policy for PositionAnd {
+    fn name(&self) -> String {
+        "and".to_string()
+    }
+
+    fn check(&self, entry: &Entry) -> Result<(), String> {
+        let (first, second) = entry.positions();
+        if first && second {
+            Ok(())
+        } else {
+            Err(entry.matched_positions())
+        }
+    }
+}
+
+/// The password contains none of the substrings
+pub struct Forbidden(pub Vec<String>);
+
+impl Policy for Forbidden {
+    fn name(&self) -> String {
+        format!("forbidden:{}", self.0.join(","))
+    }
+
+    fn check(&self, entry: &Entry) -> Result<(), String> {
+        match self.0.iter().find(|s| entry.password.contains(s.as_str())) {
+            Some(s) => Err(format!("contains {:?}", s)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The password has characters of at least that many classes: lowercase and uppercase
+/// letters, digits and other symbols
+pub struct MinClasses(pub usize);
+
+/// The character classes of `MinClasses`
+fn classes(password: &str) -> usize {
+    let classes: [fn(char) -> bool; 4] = [
+        char::is_lowercase,
+        char::is_uppercase,
+        |c| c.is_ascii_digit(),
+        |c| !c.is_alphanumeric(),
+    ];
+    classes
+        .iter()
+        .filter(|&&class| password.chars().any(class))
+        .count()
+}
+
+impl Policy for MinClasses {
+    fn name(&self) -> String {
+        format!("classes:{}", self.0)
+    }
+
+    fn check(&self, entry: &Entry) -> Result<(), String> {
+        let classes = classes(&entry.password);
+        if classes >= self.0 {
+            Ok(())
+        } else {
+            let plural = if classes == 1 { "" } else { "es" };
+            Err(format!(
+                "{} class{}, expected at least {}",
+                classes, plural, self.0
+            ))
+        }
+    }
+}
+
+/// The names of the policies, as accepted by `policy`
+pub const POLICIES: &str = "count, xor, and, forbidden:<substring>,..., classes:<n>";
+
+/// The policy with the given name and parameters, see `POLICIES`
+pub fn policy(name: &str) -> Result<Box<dyn Policy>, String> {
+    let (name, param) = match name.split_once(':') {
+        Some((name, param)) => (name, Some(param)),
+        None => (name, None),
+    };
+
+    match (name, param) {
+        ("count", None) => Ok(Box::new(CountRange)),
+        ("xor", None) => Ok(Box::new(PositionXor)),
+        ("and", None) => Ok(Box::new(PositionAnd)),
+        // an empty substring would be in every password
+        ("forbidden", Some(list)) if !list.split(',').any(str::is_empty) => Ok(Box::new(
+            Forbidden(list.split(',').map(String::from).collect()),
+        )),
+        ("classes", Some(n)) => n
+            .parse()
+            .map(|n| Box::new(MinClasses(n)) as Box<dyn Policy>)
+            .map_err(|_| format!("Invalid number of classes {}", n)),
+        ("forbidden", _) => Err("Expected forbidden:<substring>,...".to_string()),
+        ("classes", None) => Err("Expected classes:<n>".to_string()),
+        _ => Err(format!(
+            "Unknown policy {}, expected one of {}",
+            name, POLICIES
+        )),
+    }
+}
+
+/// The number of entries valid by the policy
+pub fn count_valid(entries: &[Entry], policy: &dyn Policy) -> usize {
+    entries.iter().filter(|entry| policy.valid(entry)).count()
+}
+
+/// A line of the input checked by the audit, with its entry or why it could not be parsed
+#[derive(Debug, PartialEq)]
+pub struct Audited<'a> {
+    /// The line number, from 1
+    pub line: usize,
+    pub text: &'a str,
+    pub entry: Result<Entry, ParseError>,
+}
+
+/// Parse every line of the input, keeping the lines which are not entries
+pub fn audit(input: &str) -> Vec<Audited<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| Audited {
+            line: i + 1,
+            text,
+            entry: Entry::parse(text).map_err(|e| e.offset_lines(i)),
+        })
+        .collect()
+}
+
+/// How to print an audit report
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// Every line with the verdict of every policy below it
+    Text,
+    /// One row per line and policy: line, entry, policy, verdict and reason
+    Csv,
+}
+
+/// The verdict of every policy on every line of the audit, the lines which are not entries
+/// with the reason
+pub fn report(audited: &[Audited], policies: &[Box<dyn Policy>], format: Format) -> String {
+    let mut out = String::new();
+    if format == Format::Csv {
+        out += "line,entry,policy,verdict,reason\n";
+    }
+
+    for audited in audited {
+        let text = audited.text.trim();
+        let verdicts: Vec<(String, &str, String)> = match &audited.entry {
+            Ok(entry) => policies
+                .iter()
+                .map(|policy| match policy.check(entry) {
+                    Ok(()) => (policy.name(), "pass", String::new()),
+                    Err(reason) => (policy.name(), "fail", reason),
+                })
+                .collect(),
+            Err(e) => vec![(
+                String::new(),
+                "error",
+                format!("column {}: {}", e.column, e.reason),
+            )],
+        };
+
+        match format {
+            Format::Text => {
+                out += &format!("line {}: {}\n", audited.line, text);
+                for (policy, verdict, reason) in verdicts {
+                    let label = if policy.is_empty() { "" } else { ": " };
+                    out += &format!("    {}{}{}", policy, label, verdict.to_uppercase());
+                    if !reason.is_empty() {
+                        out += &format!(" {}", reason);
+                    }
+                    out += "\n";
+                }
+            }
+            Format::Csv => {
+                for (policy, verdict, reason) in verdicts {
+                    let fields = [&audited.line.to_string(), text, &policy, verdict, &reason];
+                    let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                    out += &row.join(",");
+                    out += "\n";
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// A CSV field, quoted if needed
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) || field.trim() != field {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn solve_part1(entries: &[Entry]) -> usize {
+    count_valid(entries, &CountRange)
+}
+
+pub fn solve_part2(entries: &[Entry]) -> usize {
+    count_valid(entries, &PositionXor)
+}
+
+/// `size` password entries, some of them valid by either policy
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const LETTERS: &str = "abcdefghijklmnopqrstuvwxyz";
+
+    (0..size)
+        .map(|_| {
+            let min = rng.between(1, 6);
+            let max = min + rng.between(0, 8);
+            let letter = rng.word(LETTERS, 1);
+            let len = rng.between(1, max + 3) as usize;
+            // the letter a third of the time, so the policies have something to count
+            let password: String = (0..len)
+                .map(|_| {
+                    if rng.chance(0.3) {
+                        letter.clone()
+                    } else {
+                        rng.word(LETTERS, 1)
+                    }
+                })
+                .collect();
+            format!("{}-{} {}: {}\n", min, max, letter, password)
+        })
+        .collect()
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Entry>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(entries: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(entries).into())
+    }
+
+    fn part2(entries: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(entries).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests_part1 {
+    use super::*;
+
+    #[test]
+    fn example1() {
+        let entry = Entry {
+            min_occurs: 1,
+            max_occurs: 3,
+            letter: 'a',
+            password: "abcde".to_string(),
+        };
+        assert!(entry.valid_part1());
+        assert!(entry.valid_part2());
+        assert_eq!(Ok(entry), Entry::parse("1-3 a: abcde"))
+    }
+
+    #[test]
+    fn example2() {
+        let entry = Entry {
+            min_occurs: 1,
+            max_occurs: 3,
+            letter: 'b',
+            password: "cdefg".to_string(),
+        };
+        assert!(!entry.valid_part1());
+        assert!(!entry.valid_part2());
+        assert_eq!(Ok(entry), Entry::parse("1-3 b: cdefg"))
+    }
+
+    #[test]
+    fn example3() {
+        let entry = Entry {
+            min_occurs: 2,
+            max_occurs: 9,
+            letter: 'c',
+            password: "ccccccccc".to_string(),
+        };
+        assert!(entry.valid_part1());
+        assert!(!entry.valid_part2());
+        assert_eq!(Ok(entry), Entry::parse("2-9 c: ccccccccc"))
+    }
+
+    #[test]
+    fn invalid_entries() {
+        assert_eq!(
+            parse("1-3 a: abcde\n1-3 a abcde"),
+            Err(ParseError::new(
+                2,
+                1,
+                "expected \"<min>-<max> <letter>: <password>\""
+            ))
+        );
+        assert_eq!(
+            Entry::parse("0-3 a: abcde"),
+            Err(ParseError::new(1, 1, "invalid position range"))
+        );
+    }
+
+    #[test]
+    fn accepts_any_password_characters() {
+        let entry = Entry::parse("2-4 #: a#1#B!").unwrap();
+        assert_eq!((entry.letter, entry.password.as_str()), ('#', "a#1#B!"));
+        assert!(entry.valid_part1());
+        assert!(!entry.valid_part2());
+        assert_eq!(Entry::parse("1-3 7: 7ü").unwrap().password, "7ü");
+        assert!(Entry::parse("1-3 a: abc def").is_err());
+    }
+
+    #[test]
+    fn checks_policies() {
+        let entry = Entry::parse("1-3 a: abaB1").unwrap();
+        let valid = |name| policy(name).unwrap().valid(&entry);
+        assert!(valid("count"));
+        assert!(!valid("xor"));
+        assert!(valid("and"));
+        assert!(valid("forbidden:bb,x"));
+        assert!(!valid("forbidden:aB"));
+        assert!(valid("classes:3"));
+        assert!(!valid("classes:4"));
+        assert_eq!(policy("forbidden:a,b").unwrap().name(), "forbidden:a,b");
+
+        assert!(policy("classes:x").is_err());
+        assert!(policy("forbidden").is_err());
+        for name in &["forbidden:", "forbidden:x,", "forbidden:a,,b"] {
+            assert_eq!(
+                policy(name).err(),
+                Some("Expected forbidden:<substring>,...".to_string())
+            );
+        }
+        assert!(policy("count:2").is_err());
+        assert_eq!(
+            policy("length").err(),
+            Some(format!(
+                "Unknown policy length, expected one of {}",
+                POLICIES
+            ))
+        );
+
+        let entries = parse("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n").unwrap();
+        assert_eq!(count_valid(&entries, &PositionAnd), 1);
+        assert_eq!(count_valid(&entries, &MinClasses(1)), 3);
+    }
+
+    #[test]
+    fn explains_failures() {
+        let entry = Entry::parse("1-3 b: cdbbbb").unwrap();
+        assert_eq!(
+            CountRange.check(&entry),
+            Err("4 times 'b', expected 1 to 3".to_string())
+        );
+        assert_eq!(PositionXor.check(&entry), Ok(()));
+        assert_eq!(
+            PositionAnd.check(&entry),
+            Err("'b' at position 3 only".to_string())
+        );
+        assert_eq!(
+            Forbidden(vec!["x".to_string(), "bb".to_string()]).check(&entry),
+            Err("contains \"bb\"".to_string())
+        );
+        assert_eq!(
+            MinClasses(2).check(&entry),
+            Err("1 class, expected at least 2".to_string())
+        );
+    }
+
+    #[test]
+    fn reports_audits() {
+        let input = "1-3 a: abcde\n1-3 b: cdefg\n1-3 a abcde\n2-9 c: ccc,\"cc\n";
+        let audited = audit(input);
+        assert_eq!(audited.len(), 4);
+        assert_eq!(audited[2].text, "1-3 a abcde");
+        assert_eq!(audited[2].entry.as_ref().unwrap_err().line, 3);
+
+        let policies = vec![policy("count").unwrap(), policy("xor").unwrap()];
+        assert_eq!(
+            report(&audited[1..3], &policies, Format::Text),
+            "\
+line 2: 1-3 b: cdefg
+    count: FAIL 0 times 'b', expected 1 to 3
+    xor: FAIL 'b' at neither position 1 nor 3
+line 3: 1-3 a abcde
+    ERROR column 1: expected \"<min>-<max> <letter>: <password>\"
+"
+        );
+        assert_eq!(
+            report(&audited, &policies[..1], Format::Csv),
+            "\
+line,entry,policy,verdict,reason
+1,1-3 a: abcde,count,pass,
+2,1-3 b: cdefg,count,fail,\"0 times 'b', expected 1 to 3\"
+3,1-3 a abcde,,error,\"column 1: expected \"\"<min>-<max> <letter>: <password>\"\"\"
+4,\"2-9 c: ccc,\"\"cc\",count,pass,
+"
+        );
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let entries = parse(&generate(&mut Rng::new(seed), 100)).unwrap();
+            assert_eq!(entries.len(), 100);
+            assert!(solve_part1(&entries) > 0);
+            assert!(solve_part2(&entries) > 0);
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Day 3: Toboggan Trajectory
+
+use crate::grid::Grid;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use std::fmt;
+use std::str::FromStr;
+
+/// A slope of the toboggan: the squares it moves to the right, to the left if negative, for
+/// every `down` squares
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Slope {
+    pub right: isize,
+    pub down: usize,
+}
+
+impl Slope {
+    pub fn new(right: isize, down: usize) -> Self {
+        assert!(down > 0, "The toboggan must move down");
+        Self { right, down }
+    }
+}
+
+/// `<right>,<down>`, such as `3,1` or `-1,2`
+impl FromStr for Slope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid slope {}, expected <right>,<down>", s);
+        let (right, down) = s.split_once(',').ok_or_else(invalid)?;
+        let right = right.trim().parse().map_err(|_| invalid())?;
+        match down.trim().parse() {
+            Ok(down) if down > 0 => Ok(Self { right, down }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.right < 0 {
+            write!(f, "left {}, down {}", -self.right, self.down)
+        } else {
+            write!(f, "right {}, down {}", self.right, self.down)
+        }
+    }
+}
+
+/// The slopes of part 2
+pub const SLOPES: [Slope; 5] = [
+    Slope { right: 1, down: 1 },
+    Slope { right: 3, down: 1 },
+    Slope { right: 5, down: 1 },
+    Slope { right: 7, down: 1 },
+    Slope { right: 1, down: 2 },
+];
+
+/// The mark of an open square on the path in `Forest::draw`
+pub const OPEN: char = 'X';
+
+/// The mark of a tree hit on the path in `Forest::draw`
+pub const TREE: char = 'O';
+
+/// A ride down a slope and the trees encountered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ride {
+    pub slope: Slope,
+    pub trees: usize,
+}
+
+/// The map of the open squares `.` and trees `#`, repeated to the right and to the left
+#[derive(Clone, Debug, PartialEq)]
+pub struct Forest {
+    map: Grid<char>,
+}
+
+impl Forest {
+    pub fn new(map: Grid<char>) -> Self {
+        Self { map }
+    }
+
+    pub fn map(&self) -> &Grid<char> {
+        &self.map
+    }
+
+    /// The squares visited from the top left corner to the bottom, as `(col, row)` with the
+    /// column counted on the repeated map
+    pub fn path(&self, slope: Slope) -> impl Iterator<Item = (isize, usize)> {
+        (0..self.map.height())
+            .step_by(slope.down)
+            .enumerate()
+            .map(move |(i, row)| (i as isize * slope.right, row))
+    }
+
+    fn is_tree(&self, col: isize, row: usize) -> bool {
+        *self.map.wrapping(col, row as isize) == '#'
+    }
+
+    /// Count the trees encountered on the slope
+    pub fn trees(&self, slope: Slope) -> usize {
+        self.path(slope)
+            .filter(|&(col, row)| self.is_tree(col, row))
+            .count()
+    }
+
+    pub fn ride(&self, slope: Slope) -> Ride {
+        Ride {
+            slope,
+            trees: self.trees(slope),
+        }
+    }
+
+    /// The rides with the fewest and the most trees of every slope up to `bound` squares to the
+    /// right or to the left and from 1 to `bound` squares down.  Ties go to the first slope,
+    /// the steepest, then the leftmost.
+    pub fn extremes(&self, bound: usize) -> (Ride, Ride) {
+        let bound = bound.max(1);
+        let rides = (1..=bound).rev().flat_map(|down| {
+            let bound = bound as isize;
+            (-bound..=bound).map(move |right| self.ride(Slope::new(right, down)))
+        });
+
+        let mut rides = rides.peekable();
+        let first = *rides.peek().unwrap();
+        rides.fold((first, first), |(fewest, most), ride| {
+            (
+                if ride.trees < fewest.trees {
+                    ride
+                } else {
+                    fewest
+                },
+                if ride.trees > most.trees { ride } else { most },
+            )
+        })
+    }
+
+    /// The map with the path drawn in, `TREE` on the trees the toboggan hits after the start and
+    /// `OPEN` on the open squares it passes.  The map is repeated as many times as the path
+    /// needs, to the left for negative slopes.
+    pub fn draw(&self, slope: Slope) -> String {
+        let width = self.map.width() as isize;
+        let copies: Vec<isize> = self
+            .path(slope)
+            .map(|(col, _)| col.div_euclid(width))
+            .collect();
+        let first = copies.iter().min().copied().unwrap_or(0);
+        let last = copies.iter().max().copied().unwrap_or(0);
+        let on_path = |col: isize, row: usize| {
+            row > 0
+                && row.is_multiple_of(slope.down)
+                && col == (row / slope.down) as isize * slope.right
+        };
+
+        let mut drawing = String::new();
+        for row in 0..self.map.height() {
+            for col in first * width..(last + 1) * width {
+                drawing.push(match (on_path(col, row), self.is_tree(col, row)) {
+                    (true, false) => OPEN,
+                    (true, true) => TREE,
+                    (false, _) => *self.map.wrapping(col, row as isize),
+                });
+            }
+            drawing.push('\n');
+        }
+        drawing
+    }
+}
+
+pub fn parse(input: &str) -> Result<Forest, ParseError> {
+    Grid::parse(input, ".#").map(Forest::new)
+}
+
+/// Count the trees encountered on the slope `right`, `down`
+pub fn trees(forest: &Forest, right: usize, down: usize) -> usize {
+    forest.trees(Slope::new(right as isize, down))
+}
+
+pub fn solve_part1(forest: &Forest) -> usize {
+    forest.trees(SLOPES[1])
+}
+
+pub fn solve_part2(forest: &Forest) -> usize {
+    SLOPES.iter().map(|&slope| forest.trees(slope)).product()
+}
+
+/// A map of `size` rows, at least one, between 11 and 31 columns wide with a tree on about a
+/// quarter of the squares
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let width = rng.between(11, 31) as usize;
+    (0..size.max(1))
+        .map(|_| {
+            let row: String = (0..width)
+                .map(|_| if rng.chance(0.25) { '#' } else { '.' })
+                .collect();
+            row + "\n"
+        })
+        .collect()
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Forest;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(forest: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(forest).into())
+    }
+
+    fn part2(forest: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(forest).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+        ..##.......
+        #...#...#..
+        .#....#..#.
+        ..#.#...#.#
+        .#...##..#.
+        ..#.##.....
+        .#.#.#....#
+        .#........#
+        #.##...#...
+        #...##....#
+        .#..#...#.#
+    ";
+
+    #[test]
+    fn example() {
+        let example = parse(EXAMPLE).unwrap();
+
+        assert_eq!(trees(&example, 1, 1), 2);
+        assert_eq!(trees(&example, 3, 1), 7);
+        assert_eq!(trees(&example, 5, 1), 3);
+        assert_eq!(trees(&example, 7, 1), 4);
+        assert_eq!(trees(&example, 1, 2), 2);
+        assert_eq!(solve_part2(&example), 336);
+
+        // O on the trees hit, X on open squares
+        assert_eq!((OPEN, TREE), ('X', 'O'));
+        let drawing = example.draw(SLOPES[1]);
+        let rows: Vec<&str> = drawing.lines().collect();
+        assert_eq!(rows.len(), 11);
+        assert_eq!(rows[0], "..##.........##.........##.......");
+        assert_eq!(rows[1], "#..X#...#..#...#...#..#...#...#..");
+        assert_eq!(rows[2], ".#....O..#..#....#..#..#....#..#.");
+        assert_eq!(rows[10], ".#..#...#.#.#..#...#.#.#..#...O.#");
+        assert_eq!(drawing.matches([OPEN, TREE]).count(), 10);
+        // the 7 trees hit of part 1
+        assert_eq!(drawing.matches(TREE).count(), 7);
+    }
+
+    #[test]
+    fn rides_left() {
+        let example = parse(EXAMPLE).unwrap();
+        // mirrored around the first column, where the toboggan starts
+        let mirrored: String = EXAMPLE
+            .lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .map(|row| row[..1].to_string() + &row[1..].chars().rev().collect::<String>() + "\n")
+            .collect();
+        let mirrored = parse(&mirrored).unwrap();
+
+        for slope in SLOPES.iter() {
+            let left = Slope::new(-slope.right, slope.down);
+            assert_eq!(mirrored.trees(left), example.trees(*slope), "{}", left);
+        }
+
+        let drawing = example.draw(Slope::new(-3, 1));
+        assert_eq!(drawing.lines().next().unwrap().len(), 44);
+        // the start is in the rightmost copy, a tree is hit on row 1 and row 10 is open
+        assert_eq!(drawing.lines().nth(1).unwrap().find(TREE), Some(30));
+        assert_eq!(drawing.lines().nth(10).unwrap().find(OPEN), Some(3));
+    }
+
+    #[test]
+    fn finds_extremes() {
+        let example = parse(EXAMPLE).unwrap();
+        let (fewest, most) = example.extremes(3);
+        assert_eq!(fewest.trees, 0);
+        assert!(SLOPES
+            .iter()
+            .all(|&slope| example.trees(slope) <= most.trees));
+        assert_eq!(example.ride(most.slope), most);
+        assert_eq!(example.ride(fewest.slope), fewest);
+
+        assert_eq!("3,1".parse(), Ok(Slope::new(3, 1)));
+        assert_eq!(" -1 , 2".parse(), Ok(Slope::new(-1, 2)));
+        assert!("3,0".parse::<Slope>().is_err());
+        assert!("3".parse::<Slope>().is_err());
+        assert_eq!(Slope::new(-1, 2).to_string(), "left 1, down 2");
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let map = parse(&generate(&mut Rng::new(seed), 50)).unwrap();
+            assert_eq!(map.map().height(), 50);
+            assert!(solve_part1(&map) <= 50);
+            solve_part2(&map);
+        }
+    }
+}
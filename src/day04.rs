@@ -0,0 +1,429 @@
+//! Day 4: Passport Processing
+
+use crate::input::paragraphs;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use regex::Regex;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub enum Field {
+    BirthYear,
+    CountryID,
+    ExpirationYear,
+    EyeColor,
+    HairColor,
+    Height,
+    IssueYear,
+    PassportID,
+}
+
+impl Field {
+    fn new(name: &str) -> Option<Self> {
+        match name {
+            "byr" => Some(Self::BirthYear),
+            "cid" => Some(Self::CountryID),
+            "ecl" => Some(Self::EyeColor),
+            "eyr" => Some(Self::ExpirationYear),
+            "hcl" => Some(Self::HairColor),
+            "hgt" => Some(Self::Height),
+            "iyr" => Some(Self::IssueYear),
+            "pid" => Some(Self::PassportID),
+            _ => None,
+        }
+    }
+}
+
+/// A passport: the fields and their values
+pub type Entry = HashMap<Field, String>;
+
+pub fn valid_part1(entry: &Entry) -> bool {
+    let cid = entry.contains_key(&Field::CountryID);
+    let count = entry.keys().count();
+    matches!((count, cid), (8, _) | (7, false))
+}
+
+fn valid_height(input: &str) -> Option<bool> {
+    let re = Regex::new(r"^(?P<value>[[:digit:]]+)(?P<unit>[[:alpha:]]+)$").unwrap();
+    let captures = re.captures(input)?;
+
+    let value: i32 = captures.name("value")?.as_str().parse().ok()?;
+    let unit = captures.name("unit")?.as_str();
+
+    match unit {
+        "cm" => Some((150..=193).contains(&value)),
+        "in" => Some((59..=76).contains(&value)),
+        _ => None,
+    }
+}
+
+fn valid_hair_color(input: &str) -> Option<bool> {
+    let re = Regex::new(r"^#(?P<color>[[:digit:][a-f]]{6}$)").unwrap();
+    let captures = re.captures(input)?;
+
+    captures.name("color").map(|_| true)
+}
+
+fn valid_eye_color(input: &str) -> Option<bool> {
+    Some(["amb", "blu", "brn", "gry", "grn", "hzl", "oth"].contains(&input))
+}
+
+fn valid_passport_id(input: &str) -> Option<bool> {
+    let re = Regex::new(r"^(?P<pid>[[:digit:]]{9}$)").unwrap();
+    let captures = re.captures(input)?;
+
+    captures.name("pid").map(|_| true)
+}
+
+pub fn valid_part2(entry: &Entry) -> bool {
+    let byr = entry
+        .get(&Field::BirthYear)
+        .and_then(|year| year.parse().ok())
+        .map(|year| (1920..=2002).contains(&year));
+
+    let iyr = entry
+        .get(&Field::IssueYear)
+        .and_then(|year| year.parse().ok())
+        .map(|year| (2010..=2020).contains(&year));
+
+    let eyr = entry
+        .get(&Field::ExpirationYear)
+        .and_then(|year| year.parse().ok())
+        .map(|year| (2020..=2030).contains(&year));
+
+    let hgt = entry
+        .get(&Field::Height)
+        .and_then(|height| valid_height(height));
+
+    let hcl = entry
+        .get(&Field::HairColor)
+        .and_then(|color| valid_hair_color(color));
+
+    let ecl = entry
+        .get(&Field::EyeColor)
+        .and_then(|color| valid_eye_color(color));
+
+    let pid = entry
+        .get(&Field::PassportID)
+        .and_then(|id| valid_passport_id(id));
+
+    // cid ignored
+
+    byr == Some(true)
+        && iyr == Some(true)
+        && eyr == Some(true)
+        && hgt == Some(true)
+        && hcl == Some(true)
+        && ecl == Some(true)
+        && pid == Some(true)
+}
+
+pub fn parse_line(line: &str) -> Result<Entry, ParseError> {
+    let mut ret = Entry::new();
+
+    for parts in line.split_whitespace() {
+        let (field, value) = parts
+            .split_once(':')
+            .ok_or_else(|| ParseError::at(line, parts, "expected \"<field>:<value>\""))?;
+
+        let field = Field::new(field)
+            .ok_or_else(|| ParseError::at(line, parts, format!("unknown field {:?}", field)))?;
+        ret.insert(field, value.to_string());
+    }
+
+    Ok(ret)
+}
+
+pub fn parse(input: &str) -> Result<Vec<Entry>, ParseError> {
+    let mut entries = Vec::<Entry>::new();
+
+    for group in paragraphs(input) {
+        let mut cur = Entry::new();
+        for line in group.lines() {
+            let kv = parse_line(line).map_err(|e| e.within(input, line))?;
+            for (field, value) in kv {
+                cur.insert(field, value);
+            }
+        }
+        entries.push(cur);
+    }
+
+    Ok(entries)
+}
+
+pub fn solve_part1(entries: &[Entry]) -> usize {
+    entries.iter().filter(|&e| valid_part1(e)).count()
+}
+
+pub fn solve_part2(entries: &[Entry]) -> usize {
+    entries.iter().filter(|&e| valid_part2(e)).count()
+}
+
+/// A value for the field `name`, valid or not by the rules of part 2
+fn field_value(rng: &mut Rng, name: &str, valid: bool) -> String {
+    const HEX: &str = "0123456789abcdef";
+    const DIGITS: &str = "0123456789";
+
+    let year = |rng: &mut Rng, low, high| {
+        if valid {
+            rng.between(low, high)
+        } else if rng.chance(0.5) {
+            rng.between(low - 20, low - 1)
+        } else {
+            rng.between(high + 1, high + 20)
+        }
+        .to_string()
+    };
+
+    match (name, valid) {
+        ("byr", _) => year(rng, 1920, 2002),
+        ("iyr", _) => year(rng, 2010, 2020),
+        ("eyr", _) => year(rng, 2020, 2030),
+        ("hgt", true) if rng.chance(0.5) => format!("{}cm", rng.between(150, 193)),
+        ("hgt", true) => format!("{}in", rng.between(59, 76)),
+        ("hgt", false) => match rng.below(3) {
+            0 => format!("{}cm", rng.between(100, 149)),
+            1 => format!("{}in", rng.between(77, 99)),
+            _ => rng.between(150, 193).to_string(),
+        },
+        ("hcl", true) => format!("#{}", rng.word(HEX, 6)),
+        ("hcl", false) if rng.chance(0.5) => rng.word(HEX, 6),
+        ("hcl", false) => format!("#{}", rng.word("ghijxyz", 6)),
+        ("ecl", true) => rng
+            .choose(&["amb", "blu", "brn", "gry", "grn", "hzl", "oth"])
+            .to_string(),
+        ("ecl", false) => rng.choose(&["blk", "wht", "xry", "zzz"]).to_string(),
+        ("pid", true) => rng.word(DIGITS, 9),
+        ("pid", false) => {
+            let len = *rng.choose(&[8, 10]);
+            rng.word(DIGITS, len)
+        }
+        _ => rng.between(1, 350).to_string(),
+    }
+}
+
+/// A batch of `size` passports, some with missing fields or invalid values
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const FIELDS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+    let passports: Vec<String> = (0..size)
+        .map(|_| {
+            let mut fields = vec![];
+            for name in FIELDS {
+                if rng.chance(if name == "cid" { 0.5 } else { 0.9 }) {
+                    let valid = rng.chance(0.9);
+                    fields.push(format!("{}:{}", name, field_value(rng, name, valid)));
+                }
+            }
+            if fields.is_empty() {
+                fields.push(format!("pid:{}", field_value(rng, "pid", true)));
+            }
+            rng.shuffle(&mut fields);
+
+            let mut passport = String::new();
+            for (i, field) in fields.iter().enumerate() {
+                if i > 0 {
+                    passport.push(if rng.chance(0.3) { '\n' } else { ' ' });
+                }
+                passport.push_str(field);
+            }
+            passport + "\n"
+        })
+        .collect();
+
+    passports.join("\n")
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<Entry>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(entries: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(entries).into())
+    }
+
+    fn part2(entries: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(entries).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example1() {
+        let entry = Entry::new();
+        assert!(!valid_part1(&entry));
+    }
+
+    #[test]
+    fn complete_record() {
+        let mut entry = Entry::new();
+
+        entry.insert(Field::BirthYear, "1937".to_string());
+        entry.insert(Field::CountryID, "147".to_string());
+        entry.insert(Field::ExpirationYear, "2020".to_string());
+        entry.insert(Field::EyeColor, "gry".to_string());
+        entry.insert(Field::HairColor, "#fffffd".to_string());
+        entry.insert(Field::Height, "183cm".to_string());
+        entry.insert(Field::IssueYear, "2017".to_string());
+        entry.insert(Field::PassportID, "860033327".to_string());
+
+        assert!(valid_part1(&entry));
+    }
+
+    #[test]
+    fn missing_height() {
+        let mut entry = Entry::new();
+
+        entry.insert(Field::BirthYear, "1937".to_string());
+        entry.insert(Field::CountryID, "147".to_string());
+        entry.insert(Field::ExpirationYear, "2020".to_string());
+        entry.insert(Field::EyeColor, "gry".to_string());
+        entry.insert(Field::HairColor, "#fffffd".to_string());
+        entry.insert(Field::IssueYear, "2017".to_string());
+        entry.insert(Field::PassportID, "860033327".to_string());
+
+        assert!(!valid_part1(&entry));
+        assert!(!valid_part2(&entry));
+    }
+
+    #[test]
+    fn missing_cid() {
+        let mut entry = Entry::new();
+
+        entry.insert(Field::BirthYear, "1937".to_string());
+        entry.insert(Field::ExpirationYear, "2020".to_string());
+        entry.insert(Field::EyeColor, "gry".to_string());
+        entry.insert(Field::HairColor, "#fffffd".to_string());
+        entry.insert(Field::Height, "183cm".to_string());
+        entry.insert(Field::IssueYear, "2017".to_string());
+        entry.insert(Field::PassportID, "860033327".to_string());
+
+        assert!(valid_part1(&entry));
+        assert!(valid_part2(&entry));
+    }
+
+    #[test]
+    fn parse_empty_line() {
+        assert_eq!(parse_line(""), Ok(Entry::new()));
+    }
+
+    #[test]
+    fn parse_record_fragment() {
+        let mut entry = Entry::new();
+
+        entry.insert(Field::BirthYear, "1937".to_string());
+        entry.insert(Field::ExpirationYear, "2020".to_string());
+        entry.insert(Field::EyeColor, "gry".to_string());
+
+        assert_eq!(parse_line("byr:1937 ecl:gry eyr:2020"), Ok(entry));
+    }
+
+    #[test]
+    fn parse_invalid_field() {
+        assert_eq!(
+            parse("byr:1937\n\necl:gry foo:bar"),
+            Err(ParseError::new(3, 9, "unknown field \"foo\""))
+        );
+        assert_eq!(
+            parse("byr:1937\r\n \r\necl:gry foo:bar\r\n"),
+            Err(ParseError::new(3, 9, "unknown field \"foo\""))
+        );
+        assert_eq!(parse("byr:1937\r\n\t\r\necl:gry\r\n").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn height_parser() {
+        assert_eq!(valid_height("180cm"), Some(true));
+        assert_eq!(valid_height("200cm"), Some(false));
+        assert_eq!(valid_height("not height"), None);
+    }
+
+    #[test]
+    fn hair_color_parser() {
+        assert_eq!(valid_hair_color("#123456"), Some(true));
+        assert_eq!(valid_hair_color("#abcfef"), Some(true));
+    }
+
+    #[test]
+    fn pid_parser() {
+        assert_eq!(valid_passport_id("000000001"), Some(true));
+        assert_eq!(valid_passport_id("0123456789"), None);
+    }
+
+    #[test]
+    fn part2_valid_passports() {
+        let text = "
+            pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+            hcl:#623a2f
+
+            eyr:2029 ecl:blu cid:129 byr:1989
+            iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm
+
+            hcl:#888785
+            hgt:164cm byr:2001 iyr:2015 cid:88
+            pid:545766238 ecl:hzl
+            eyr:2022
+
+            iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
+        ";
+
+        assert_eq!(
+            parse(text)
+                .unwrap()
+                .iter()
+                .filter(|&e| valid_part2(e))
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn part2_invalid_passports() {
+        let text = "
+            eyr:1972 cid:100
+            hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+            iyr:2019
+            hcl:#602927 eyr:1967 hgt:170cm
+            ecl:grn pid:012533040 byr:1946
+
+            hcl:dab227 iyr:2012
+            ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
+
+            hgt:59cm ecl:zzz
+            eyr:2038 hcl:74454a iyr:2023
+            pid:3556412378 byr:2007
+        ";
+
+        assert_eq!(
+            parse(text)
+                .unwrap()
+                .iter()
+                .filter(|&e| valid_part2(e))
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let passports = parse(&generate(&mut Rng::new(seed), 100)).unwrap();
+            assert_eq!(passports.len(), 100);
+            let (part1, part2) = (solve_part1(&passports), solve_part2(&passports));
+            assert!(0 < part2 && part2 < part1 && part1 < 100);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Day 5: Binary Boarding
+
+use crate::error::only_chars;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+
+fn decode(text: &str, base: i32, _lower: &char, upper: &char) -> i32 {
+    let mut base = base;
+    let mut offset = 0;
+
+    for c in text.chars() {
+        base /= 2;
+        if c == *upper {
+            offset += base
+        }
+    }
+
+    offset
+}
+
+pub fn decode_row(text: &str) -> i32 {
+    decode(text, 128, &'F', &'B')
+}
+
+pub fn decode_column(text: &str) -> i32 {
+    decode(text, 8, &'L', &'R')
+}
+
+/// Decode the seat IDs of the boarding passes
+pub fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+    let seat_ids = input
+        .lines()
+        .map(|line| line.trim())
+        .map(|line| {
+            if line.len() != 10 {
+                return Err(ParseError::at(input, line, "expected 10 characters"));
+            }
+            let (row, col) = line.split_at(7);
+            only_chars(input, row, "FB")?;
+            only_chars(input, col, "LR")?;
+            Ok(8 * decode_row(row) + decode_column(col))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if seat_ids.is_empty() {
+        return Err(ParseError::at_end(input, "no boarding passes"));
+    }
+
+    Ok(seat_ids)
+}
+
+pub fn solve_part1(seat_ids: &[i32]) -> i32 {
+    *seat_ids.iter().max().unwrap()
+}
+
+/// The free seat between two taken ones, `None` if there is none
+pub fn solve_part2(seat_ids: &[i32]) -> Option<i32> {
+    let mut r = seat_ids.to_vec();
+
+    r.sort();
+
+    let mut x = r
+        .windows(2)
+        .map(|w| (w[0], w[1] - w[0]))
+        .filter(|(_, diff)| *diff == 2);
+
+    let (seat_id, _) = x.next()?;
+
+    Some(seat_id + 1)
+}
+
+/// The boarding passes of `size` seats, between 3 and 1023, with consecutive IDs except for
+/// one missing in between
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let count = size.clamp(3, 1023) as i32;
+    let first = rng.between(0, (1023 - count) as i64) as i32;
+    let missing = first + rng.between(1, count as i64 - 1) as i32;
+
+    let mut seat_ids: Vec<i32> = (first..=first + count)
+        .filter(|&id| id != missing)
+        .collect();
+    rng.shuffle(&mut seat_ids);
+
+    let bits = |n: i32, width: usize, zero: char, one: char| -> String {
+        (0..width)
+            .rev()
+            .map(|bit| if n >> bit & 1 == 1 { one } else { zero })
+            .collect()
+    };
+    seat_ids
+        .iter()
+        .map(|id| {
+            format!(
+                "{}{}\n",
+                bits(id / 8, 7, 'F', 'B'),
+                bits(id % 8, 3, 'L', 'R')
+            )
+        })
+        .collect()
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(seat_ids: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(seat_ids).into())
+    }
+
+    fn part2(seat_ids: &Self::Input) -> Result<Answer, String> {
+        solve_part2(seat_ids)
+            .map(Answer::from)
+            .ok_or_else(|| "no free seat between two taken ones".to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_rows() {
+        assert_eq!(decode_row("BFFFBBF"), 70);
+        assert_eq!(decode_row("FFFBBBF"), 14);
+        assert_eq!(decode_row("BBFFBBF"), 102);
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert_eq!(parse(""), Err(ParseError::new(1, 1, "no boarding passes")));
+    }
+
+    #[test]
+    fn finds_no_free_seat() {
+        assert_eq!(solve_part2(&[8]), None);
+        assert_eq!(solve_part2(&[9, 8]), None);
+        assert_eq!(solve_part2(&[10, 8]), Some(9));
+        assert_eq!(
+            Day05::solve(&vec![8, 9], 2),
+            Some(Err("no free seat between two taken ones".to_string()))
+        );
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let seat_ids = parse(&generate(&mut Rng::new(seed), 100)).unwrap();
+            assert_eq!(seat_ids.len(), 100);
+            let seat = solve_part2(&seat_ids).unwrap();
+            assert!(!seat_ids.contains(&seat));
+            assert!(seat_ids.contains(&(seat - 1)) && seat_ids.contains(&(seat + 1)));
+            assert_eq!(solve_part1(&seat_ids) - seat_ids.iter().min().unwrap(), 100);
+        }
+    }
+}
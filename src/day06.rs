@@ -0,0 +1,131 @@
+//! Day 6: Custom Customs
+
+use crate::error::only_chars;
+use crate::input::paragraphs;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use std::collections::HashSet;
+
+/// A group of passengers: the answers of each person
+pub type Group = Vec<String>;
+
+pub fn parse(input: &str) -> Result<Vec<Group>, ParseError> {
+    paragraphs(input)
+        .map(|group| {
+            group
+                .lines()
+                .map(|person| {
+                    let person = person.trim();
+                    only_chars(input, person, "abcdefghijklmnopqrstuvwxyz")?;
+                    Ok(person.to_string())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn solve_part1(groups: &[Group]) -> usize {
+    let mut sum = 0;
+
+    for group in groups {
+        let mut questions: HashSet<char> = HashSet::new();
+
+        for person in group {
+            for answer in person.chars() {
+                questions.insert(answer);
+            }
+        }
+
+        sum += questions.len();
+    }
+
+    sum
+}
+
+fn common_answers(a: &str, b: &str) -> String {
+    let a: HashSet<char> = a.chars().collect();
+    let b: HashSet<char> = b.chars().collect();
+
+    a.intersection(&b).collect()
+}
+
+pub fn solve_part2(groups: &[Group]) -> usize {
+    let mut sum = 0;
+
+    for group in groups {
+        sum += group
+            .iter()
+            .fold(group[0].to_string(), |acc, x| common_answers(&acc, x))
+            .len();
+    }
+
+    sum
+}
+
+/// The answers of `size` groups, at least one, of one to five people each answering yes to at
+/// least one question
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let questions: Vec<char> = ('a'..='z').collect();
+
+    let groups: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            // people in a group share some answers, so part 2 has something to count
+            let shared = rng.below(4);
+            let common = rng.sample(&questions, shared);
+            (0..rng.between(1, 5))
+                .map(|_| {
+                    let count = rng.between(1, 10) as usize;
+                    let mut answers = rng.sample(&questions, count);
+                    answers.retain(|c| !common.contains(c));
+                    answers.extend(&common);
+                    if answers.is_empty() {
+                        answers.push(*rng.choose(&questions));
+                    }
+                    rng.shuffle(&mut answers);
+                    answers.into_iter().collect::<String>() + "\n"
+                })
+                .collect()
+        })
+        .collect();
+
+    groups.join("\n")
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<Group>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(groups: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(groups).into())
+    }
+
+    fn part2(groups: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(groups).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let groups = parse(&generate(&mut Rng::new(seed), 50)).unwrap();
+            assert_eq!(groups.len(), 50);
+            assert!(groups
+                .iter()
+                .all(|group| group.iter().all(|p| !p.is_empty())));
+            assert!(solve_part2(&groups) <= solve_part1(&groups));
+        }
+    }
+}
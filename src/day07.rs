@@ -0,0 +1,366 @@
+//! Day 7: Handy Haversacks
+
+use crate::error::number;
+use crate::random::Rng;
+use crate::repl::Reply;
+use crate::{Answer, ParseError, Solution};
+use std::collections::HashMap;
+
+pub fn parse_line(line: &str) -> Result<(String, Vec<(i32, String)>), ParseError> {
+    let (bag, content) = line
+        .trim()
+        .split_once(" bags contain ")
+        .ok_or_else(|| ParseError::at(line, line, "expected \"<color> bags contain ...\""))?;
+
+    let mut res = vec![];
+
+    let content = content
+        .strip_suffix('.')
+        .ok_or_else(|| ParseError::at_end(line, "expected '.'"))?;
+
+    if content == "no other bags" {
+        return Ok((bag.to_string(), res));
+    }
+
+    for content in content.split(", ") {
+        let words: Vec<&str> = content.split(' ').collect();
+        match words[..] {
+            [qty, adjective, color, "bag"] | [qty, adjective, color, "bags"] => {
+                res.push((number(line, qty)?, format!("{} {}", adjective, color)))
+            }
+            _ => {
+                return Err(ParseError::at(
+                    line,
+                    content,
+                    "expected \"<quantity> <color> bags\"",
+                ))
+            }
+        }
+    }
+
+    Ok((bag.to_string(), res))
+}
+
+/// The contents of each bag: the quantity and the color of the inner bags
+pub type Rules = HashMap<String, Vec<(i32, String)>>;
+
+pub fn parse(input: &str) -> Result<Rules, ParseError> {
+    let mut rules = Rules::new();
+    for (i, line) in input.lines().enumerate() {
+        let (bag_color, contents) = parse_line(line).map_err(|e| e.offset_lines(i))?;
+        if rules.contains_key(&bag_color) {
+            let reason = format!("duplicate rule for {} bags", bag_color);
+            return Err(ParseError::at(input, line.trim_start(), reason));
+        }
+        rules.insert(bag_color, contents);
+    }
+
+    for line in input.lines() {
+        let (_, contents) = parse_line(line)?;
+        for (_, color) in contents {
+            if !rules.contains_key(&color) {
+                let at = &line[line.find(&color).unwrap()..];
+                return Err(ParseError::at(
+                    input,
+                    at,
+                    format!("no rule for {} bags", color),
+                ));
+            }
+        }
+    }
+
+    if !rules.contains_key("shiny gold") {
+        return Err(ParseError::at_end(input, "no rule for shiny gold bags"));
+    }
+
+    Ok(rules)
+}
+
+pub fn count_shiny_gold(rules: &Rules, color: &str) -> i32 {
+    let target = "shiny gold";
+    let mut sum = 0;
+
+    for (_qty, value) in &rules[color] {
+        if value == target {
+            sum += 1;
+        } else {
+            sum += count_shiny_gold(rules, value);
+        }
+    }
+
+    sum
+}
+
+pub fn count_bags(rules: &Rules, color: &str) -> i32 {
+    rules[color]
+        .iter()
+        .map(|(num_bags, color)| num_bags + num_bags * count_bags(rules, color))
+        .sum()
+}
+
+/// The colors of the bags which eventually contain a bag of the given color, sorted
+pub fn holders<'a>(rules: &'a Rules, color: &str) -> Vec<&'a str> {
+    let mut holders: Vec<&str> = vec![];
+    let mut todo = vec![color];
+    while let Some(inner) = todo.pop() {
+        for (outer, contents) in rules {
+            if !holders.contains(&outer.as_str()) && contents.iter().any(|(_, c)| c == inner) {
+                holders.push(outer);
+                todo.push(outer);
+            }
+        }
+    }
+
+    holders.sort_unstable();
+    holders
+}
+
+pub fn solve_part1(rules: &Rules) -> usize {
+    rules
+        .keys()
+        .map(|color| count_shiny_gold(rules, color))
+        .filter(|&x| x > 0)
+        .count()
+}
+
+pub fn solve_part2(rules: &Rules) -> i32 {
+    count_bags(rules, "shiny gold")
+}
+
+/// The rules of `size` bag colors, between 2 and 594, every color in one of five layers
+/// containing bags of the deeper layers only.  Shiny gold bags are in the middle layer.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const ADJECTIVES: [&str; 18] = [
+        "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+        "muted", "pale", "plaid", "posh", "shiny", "striped", "vibrant", "wavy",
+    ];
+    const COLORS: [&str; 33] = [
+        "aqua",
+        "beige",
+        "black",
+        "blue",
+        "bronze",
+        "brown",
+        "chartreuse",
+        "coral",
+        "crimson",
+        "cyan",
+        "fuchsia",
+        "gold",
+        "gray",
+        "green",
+        "indigo",
+        "lavender",
+        "lime",
+        "magenta",
+        "maroon",
+        "olive",
+        "orange",
+        "plum",
+        "purple",
+        "red",
+        "salmon",
+        "silver",
+        "tan",
+        "teal",
+        "tomato",
+        "turquoise",
+        "violet",
+        "white",
+        "yellow",
+    ];
+    const LAYERS: usize = 5;
+
+    let all: Vec<String> = ADJECTIVES
+        .iter()
+        .flat_map(|adjective| {
+            COLORS
+                .iter()
+                .map(move |color| format!("{} {}", adjective, color))
+        })
+        .filter(|bag| bag != "shiny gold")
+        .collect();
+    let mut bags = vec![("shiny gold".to_string(), LAYERS / 2)];
+    for bag in rng.sample(&all, size.clamp(2, all.len() + 1) - 1) {
+        bags.push((bag, rng.below(LAYERS)));
+    }
+
+    let mut rules: Vec<String> = bags
+        .iter()
+        .map(|(bag, layer)| {
+            let deeper: Vec<&String> = bags
+                .iter()
+                .filter(|(_, l)| l > layer)
+                .map(|(inner, _)| inner)
+                .collect();
+            let count = rng.below(4).min(deeper.len());
+            let contents: Vec<String> = rng
+                .sample(&deeper, count)
+                .iter()
+                .map(|inner| match rng.between(1, 5) {
+                    1 => format!("1 {} bag", inner),
+                    n => format!("{} {} bags", n, inner),
+                })
+                .collect();
+
+            if contents.is_empty() {
+                format!("{} bags contain no other bags.\n", bag)
+            } else {
+                format!("{} bags contain {}.\n", bag, contents.join(", "))
+            }
+        })
+        .collect();
+
+    rng.shuffle(&mut rules);
+    rules.concat()
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Rules;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(rules: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(rules).into())
+    }
+
+    fn part2(rules: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(rules).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    const QUERIES: &'static [(&'static str, &'static str)] = &[
+        ("contents <color>", "The bags directly inside a bag"),
+        ("total <color>", "The number of bags inside a bag"),
+        ("holders <color>", "The bags eventually containing a bag"),
+    ];
+
+    fn query(rules: &Self::Input, query: &str, args: &[&str]) -> Option<Reply> {
+        let color = args.join(" ");
+        let rule = rules
+            .get(&color)
+            .ok_or_else(|| format!("no rule for {} bags", color));
+
+        Some(match query {
+            "contents" => rule.map(|contents| match &contents[..] {
+                [] => "no other bags".to_string(),
+                contents => contents
+                    .iter()
+                    .map(|(qty, color)| format!("{} {}", qty, color))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            }),
+            "total" => rule.map(|_| count_bags(rules, &color).to_string()),
+            "holders" => rule.map(|_| {
+                let holders = holders(rules, &color);
+                format!("{} colors: {}", holders.len(), holders.join(", "))
+            }),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_common_line() {
+        assert_eq!(
+            parse_line("shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags."),
+            Ok((
+                "shiny gold".to_string(),
+                vec!(
+                    (1, "dark olive".to_string()),
+                    (2, "vibrant plum".to_string())
+                )
+            ))
+        );
+    }
+
+    #[test]
+    fn can_parse_no_other_bags_line() {
+        assert_eq!(
+            parse_line("silver blue bags contain no other bags."),
+            Ok(("silver blue".to_string(), vec![]))
+        );
+    }
+
+    #[test]
+    fn example1() {
+        let text = "\
+                light red bags contain 1 bright white bag, 2 muted yellow bags.
+                dark orange bags contain 3 bright white bags, 4 muted yellow bags.
+                bright white bags contain 1 shiny gold bag.
+                muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+                shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.
+                dark olive bags contain 3 faded blue bags, 4 dotted black bags.
+                vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.
+                faded blue bags contain no other bags.
+                dotted black bags contain no other bags.\
+            ";
+
+        let rules = parse(text).unwrap();
+
+        assert_eq!(solve_part1(&rules), 4);
+
+        assert_eq!(count_bags(&rules, "faded blue"), 0);
+        assert_eq!(count_bags(&rules, "vibrant plum"), 11);
+        assert_eq!(count_bags(&rules, "shiny gold"), 32);
+        assert_eq!(
+            holders(&rules, "shiny gold"),
+            ["bright white", "dark orange", "light red", "muted yellow"]
+        );
+        assert_eq!(holders(&rules, "light red"), Vec::<&str>::new());
+
+        assert_eq!(
+            Day07::query(&rules, "contents", &["shiny", "gold"]),
+            Some(Ok("1 dark olive, 2 vibrant plum".to_string()))
+        );
+        assert_eq!(
+            Day07::query(&rules, "contents", &["faded", "blue"]),
+            Some(Ok("no other bags".to_string()))
+        );
+        assert_eq!(
+            Day07::query(&rules, "total", &["vibrant", "plum"]),
+            Some(Ok("11".to_string()))
+        );
+        assert_eq!(
+            Day07::query(&rules, "holders", &["dark", "olive"]),
+            Some(Ok(
+                "5 colors: bright white, dark orange, light red, muted yellow, shiny gold"
+                    .to_string()
+            ))
+        );
+        assert_eq!(
+            Day07::query(&rules, "total", &["pink"]),
+            Some(Err("no rule for pink bags".to_string()))
+        );
+        assert_eq!(Day07::query(&rules, "color", &[]), None);
+    }
+
+    #[test]
+    fn rejects_undefined_bags() {
+        assert_eq!(
+            parse("shiny gold bags contain 2 dark red bags."),
+            Err(ParseError::new(1, 27, "no rule for dark red bags"))
+        );
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let rules = parse(&generate(&mut Rng::new(seed), 100)).unwrap();
+            assert_eq!(rules.len(), 100);
+            assert!(solve_part1(&rules) < 100);
+            solve_part2(&rules);
+        }
+    }
+}
@@ -0,0 +1,375 @@
+//! Day 8: Handheld Halting
+
+use crate::error::number;
+use crate::random::Rng;
+use crate::repl::{arg, Reply};
+use crate::{Answer, ParseError, Solution};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    Nop(i32),
+    Acc(i32),
+    Jmp(i32),
+}
+
+impl Op {
+    pub fn parse(text: &str) -> std::result::Result<Self, ParseError> {
+        let (name, arg) = text
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| ParseError::at_end(text, "no instruction argument"))?;
+        let arg = number(text, arg)
+            .map_err(|e| ParseError::new(e.line, e.column, "invalid instruction argument"))?;
+
+        match name {
+            "nop" => Ok(Op::Nop(arg)),
+            "acc" => Ok(Op::Acc(arg)),
+            "jmp" => Ok(Op::Jmp(arg)),
+            _ => Err(ParseError::at(text, name, "unexpected instruction")),
+        }
+    }
+}
+
+impl FromStr for Op {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Op::parse(s).map_err(|e| e.reason)
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Nop(arg) => write!(f, "nop {:+}", arg),
+            Op::Acc(arg) => write!(f, "acc {:+}", arg),
+            Op::Jmp(arg) => write!(f, "jmp {:+}", arg),
+        }
+    }
+}
+
+pub type Program = Vec<Op>;
+
+/// The accumulator on termination, or the accumulator and the visited
+/// instructions when an infinite loop is detected
+pub type Result = std::result::Result<i32, (i32, Vec<usize>)>;
+
+pub fn parse(input: &str) -> std::result::Result<Program, ParseError> {
+    let lines: Vec<&str> = input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let program = lines
+        .iter()
+        .map(|line| Op::parse(line).map_err(|e| e.within(input, line)))
+        .collect::<std::result::Result<Program, _>>()?;
+
+    if program.is_empty() {
+        return Err(ParseError::at_end(input, "no instructions"));
+    }
+
+    // a jump, or a no-op turned into one, must stay in the program or end it
+    for (pc, (op, line)) in program.iter().zip(lines).enumerate() {
+        if let Op::Jmp(offset) | Op::Nop(offset) = op {
+            if !(0..=program.len() as i64).contains(&(pc as i64 + *offset as i64)) {
+                let line = line.trim_end();
+                let at = &line[line.rfind(' ').unwrap() + 1..];
+                return Err(ParseError::at(input, at, "jump out of the program"));
+            }
+        }
+    }
+
+    Ok(program)
+}
+
+pub fn run(p: &[Op]) -> Result {
+    let mut pc = 0; // current program counter
+    let mut acc = 0; // accumulator
+
+    let mut visited = vec![];
+
+    while pc != p.len() {
+        if visited.contains(&pc) {
+            return Err((acc, visited));
+        }
+
+        visited.push(pc);
+        let inst = &p[pc];
+
+        match inst {
+            Op::Nop(_) => {
+                pc += 1;
+            }
+            Op::Acc(arg) => {
+                acc += arg;
+                pc += 1;
+            }
+            Op::Jmp(pos) => {
+                pc = (pc as i32 + pos) as usize;
+            }
+        }
+    }
+
+    Ok(acc)
+}
+
+pub fn replace_instruction(p: &[Op], i: usize) -> Program {
+    let mut p = p.to_owned();
+    match p[i] {
+        Op::Jmp(x) => p[i] = Op::Nop(x),
+        Op::Nop(x) => p[i] = Op::Jmp(x),
+        _ => {}
+    }
+
+    p
+}
+
+/// The accumulator before an instruction runs twice, `None` if the program terminates
+pub fn solve_part1(p: &[Op]) -> Option<i32> {
+    let (acc, _) = run(p).err()?;
+
+    Some(acc)
+}
+
+/// The accumulator at the end of the program with one instruction patched, `None` if the
+/// program terminates already or if no patch makes it terminate
+pub fn solve_part2(p: &[Op]) -> Option<i32> {
+    let (_, visited) = run(p).err()?;
+
+    visited
+        .into_iter()
+        .find_map(|pc| run(&replace_instruction(p, pc)).ok())
+}
+
+/// A program of `size` instructions, at least two, stuck in a loop until the one instruction
+/// closing the loop is patched
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let len = size.max(2);
+    loop {
+        // jumping forward only, the program terminates
+        let mut program: Program = (0..len)
+            .map(|pc| match rng.below(3) {
+                0 => Op::Acc(rng.between(-50, 50) as i32),
+                1 => Op::Nop(rng.between(-(pc as i64), (len - pc) as i64) as i32),
+                _ => Op::Jmp(rng.between(1, (len - pc).min(5) as i64) as i32),
+            })
+            .collect();
+
+        let mut path = vec![];
+        let mut pc = 0;
+        while pc < len {
+            path.push(pc);
+            pc = match program[pc] {
+                Op::Jmp(offset) => (pc as i32 + offset) as usize,
+                _ => pc + 1,
+            };
+        }
+        if path.len() < 2 {
+            continue;
+        }
+
+        // then a jump back to an instruction on the path closes the loop
+        let i = rng.between(1, path.len() as i64 - 1) as usize;
+        let target = path[rng.below(i)];
+        program[path[i]] = Op::Jmp(target as i32 - path[i] as i32);
+
+        // only patching that jump ends the program: a no-op turned into a jump lands back in
+        // the loop, and a jump turned into a no-op runs into a jump back to it
+        for &pc in &path[..i] {
+            match program[pc] {
+                Op::Nop(_) => {
+                    let target = path[rng.below(i + 1)];
+                    program[pc] = Op::Nop(target as i32 - pc as i32);
+                }
+                Op::Jmp(offset) if offset > 1 => program[pc + 1] = Op::Jmp(-1),
+                _ => {}
+            }
+        }
+
+        return program
+            .iter()
+            .map(|op| match op {
+                Op::Nop(arg) => format!("nop {:+}\n", arg),
+                Op::Acc(arg) => format!("acc {:+}\n", arg),
+                Op::Jmp(arg) => format!("jmp {:+}\n", arg),
+            })
+            .collect();
+    }
+}
+
+/// The instructions from `args[0]` to `args[1]`, all of them by default
+fn list(program: &[Op], args: &[&str]) -> Reply {
+    let from = match args.first() {
+        Some(_) => arg(args, 0, "instruction")?,
+        None => 0,
+    };
+    let to = match args.get(1) {
+        Some(_) => arg(args, 1, "instruction")?,
+        None => program.len(),
+    };
+
+    let lines: Vec<String> = (from..=to.min(program.len()))
+        .filter_map(|pc| Some(format!("{:4}  {}", pc, program.get(pc)?)))
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Run the program with the instruction `args[0]` patched, if given
+fn run_patched(program: &[Op], args: &[&str]) -> Reply {
+    let program = match args.first() {
+        None => program.to_vec(),
+        Some(_) => {
+            let pc: usize = arg(args, 0, "instruction")?;
+            match program.get(pc) {
+                Some(Op::Acc(_)) => return Err(format!("instruction {} is acc", pc)),
+                Some(_) => replace_instruction(program, pc),
+                None => return Err(format!("no instruction {}", pc)),
+            }
+        }
+    };
+
+    Ok(match run(&program) {
+        Ok(acc) => format!("terminates with accumulator {}", acc),
+        Err((acc, visited)) => format!(
+            "loops after {} instructions with accumulator {}",
+            visited.len(),
+            acc
+        ),
+    })
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Program;
+
+    fn parse(input: &str) -> std::result::Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(program: &Self::Input) -> std::result::Result<Answer, String> {
+        solve_part1(program)
+            .map(Answer::from)
+            .ok_or_else(|| "The program terminates without a loop".to_string())
+    }
+
+    fn part2(program: &Self::Input) -> std::result::Result<Answer, String> {
+        solve_part2(program)
+            .map(Answer::from)
+            .ok_or_else(|| "No patched instruction ends the program".to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    const QUERIES: &'static [(&'static str, &'static str)] = &[
+        ("list [<from> [<to>]]", "The instructions, numbered from 0"),
+        (
+            "run [<patch>]",
+            "Run the program, with one jmp or nop swapped",
+        ),
+    ];
+
+    fn query(program: &Self::Input, query: &str, args: &[&str]) -> Option<Reply> {
+        match query {
+            "list" => Some(list(program, args)),
+            "run" => Some(run_patched(program, args)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_instructions() {
+        assert_eq!("nop +0".parse(), Ok(Op::Nop(0)));
+        assert_eq!("acc +4".parse(), Ok(Op::Acc(4)));
+        assert_eq!("jmp -3".parse(), Ok(Op::Jmp(-3)));
+        assert_eq!(Op::Jmp(-3).to_string(), "jmp -3");
+        assert_eq!(
+            "some other string".parse::<Op>(),
+            Err("invalid instruction argument".to_string())
+        );
+        assert_eq!(
+            parse("nop +0\njmp +3"),
+            Err(ParseError::new(2, 5, "jump out of the program"))
+        );
+        assert_eq!(parse("\n"), Err(ParseError::new(2, 1, "no instructions")));
+    }
+
+    #[test]
+    fn reports_programs_without_answers() {
+        let ends = parse("nop +0\nacc +1\n").unwrap();
+        assert_eq!(solve_part1(&ends), None);
+        assert_eq!(solve_part2(&ends), None);
+        assert_eq!(
+            Day08::solve(&ends, 1),
+            Some(Err("The program terminates without a loop".to_string()))
+        );
+
+        // the patched jump runs into a jump back to it
+        let stuck = parse("acc +1\njmp -1\njmp -1\n").unwrap();
+        assert_eq!(solve_part1(&stuck), Some(1));
+        assert_eq!(solve_part2(&stuck), None);
+    }
+
+    #[test]
+    fn example1() {
+        let text = "
+            nop +0
+            acc +1
+            jmp +4
+            acc +3
+            jmp -3
+            acc -99
+            acc +1
+            jmp -4
+            acc +6
+        ";
+
+        let p = parse(text).unwrap();
+        assert_eq!(solve_part1(&p), Some(5));
+        assert_eq!(solve_part2(&p), Some(8));
+
+        assert_eq!(
+            Day08::query(&p, "list", &["3", "4"]),
+            Some(Ok("   3  acc +3\n   4  jmp -3".to_string()))
+        );
+        assert_eq!(
+            Day08::query(&p, "run", &[]),
+            Some(Ok(
+                "loops after 7 instructions with accumulator 5".to_string()
+            ))
+        );
+        assert_eq!(
+            Day08::query(&p, "run", &["7"]),
+            Some(Ok("terminates with accumulator 8".to_string()))
+        );
+        assert_eq!(
+            Day08::query(&p, "run", &["1"]),
+            Some(Err("instruction 1 is acc".to_string()))
+        );
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let program = parse(&generate(&mut Rng::new(seed), 100)).unwrap();
+            assert_eq!(program.len(), 100);
+            let (_, visited) = run(&program).unwrap_err();
+            let patches = visited
+                .iter()
+                .filter(|&&pc| run(&replace_instruction(&program, pc)).is_ok())
+                .count();
+            assert_eq!(patches, 1);
+            solve_part1(&program);
+            solve_part2(&program);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Day 9: Encoding Error
+
+use crate::error::number;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use std::collections::{HashSet, VecDeque};
+
+// Solution from day 01
+fn offending(target: i64, input: &[i64]) -> Option<i64> {
+    let input_set: HashSet<_> = input.iter().cloned().collect();
+    let complement: HashSet<_> = input.iter().map(|x| target - x).collect();
+
+    let res: HashSet<_> = input_set.intersection(&complement).collect();
+
+    for i in res.iter() {
+        for j in res.iter() {
+            if input.contains(i) && input.contains(j) {
+                return None;
+            }
+        }
+    }
+
+    Some(target)
+}
+
+pub fn solve_part1(input: &[i64], preamble: usize) -> Option<i64> {
+    input
+        .iter()
+        .skip(preamble)
+        .zip(input.windows(preamble))
+        .find_map(|(&target, elems)| offending(target, elems))
+}
+
+pub fn solve_part2(input: &[i64], target: i64) -> Option<i64> {
+    let mut buf: VecDeque<i64> = VecDeque::new();
+
+    for elem in input.iter() {
+        while buf.iter().sum::<i64>() + elem > target {
+            buf.pop_front();
+        }
+
+        buf.push_back(*elem);
+
+        if buf.iter().sum::<i64>() == target {
+            let min = buf.iter().min()?;
+            let max = buf.iter().max()?;
+            return Some(min + max);
+        }
+    }
+
+    None
+}
+
+pub fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+    input
+        .lines()
+        .map(|line| number(input, line.trim()))
+        .collect()
+}
+
+/// `size` numbers, between 30 and 1000, each after the preamble of 25 the sum of two of the 25
+/// before it, except the last which is the sum of a contiguous run instead
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let len = size.clamp(30, 1000);
+    let preamble: Vec<i64> = (1..=60).collect();
+    loop {
+        let mut numbers = rng.sample(&preamble, 25);
+        while numbers.len() < len - 1 {
+            // the sum of two of the smallest keeps the numbers from growing too fast
+            let mut window = numbers[numbers.len() - 25..].to_vec();
+            window.sort_unstable();
+            let i = rng.below(5);
+            let j = (i + rng.between(1, 4) as usize) % 5;
+            numbers.push(window[i] + window[j]);
+        }
+
+        let start = rng.below(len - 3);
+        let run = rng.between(2, (len - 1 - start).min(6) as i64) as usize;
+        let invalid: i64 = numbers[start..start + run].iter().sum();
+        // like in the puzzle, no number before the run is larger
+        let window = &numbers[len - 26..];
+        if numbers[..start].iter().all(|&x| x < invalid)
+            && window.iter().all(|x| !window.contains(&(invalid - x)))
+        {
+            numbers.push(invalid);
+            return numbers.iter().map(|x| format!("{}\n", x)).collect();
+        }
+    }
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(xmas: &Self::Input) -> Result<Answer, String> {
+        solve_part1(xmas, 25)
+            .map(Answer::from)
+            .ok_or_else(|| "Couldn't find the offending number".to_string())
+    }
+
+    fn part2(xmas: &Self::Input) -> Result<Answer, String> {
+        let invalid_number = solve_part1(xmas, 25).ok_or("Couldn't find the offending number")?;
+
+        solve_part2(xmas, invalid_number)
+            .map(Answer::from)
+            .ok_or_else(|| "Couldn't find the contiguous set".to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &[i64] = &[
+        35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
+    ];
+
+    #[test]
+    fn example1() {
+        assert_eq!(solve_part1(EXAMPLE, 5), Some(127));
+        assert_eq!(solve_part2(EXAMPLE, 127), Some(15 + 47)); // min + max between 15, 25, 47, 40
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let numbers = parse(&generate(&mut Rng::new(seed), 100)).unwrap();
+            assert_eq!(numbers.len(), 100);
+            let invalid = solve_part1(&numbers, 25);
+            assert_eq!(invalid, numbers.last().copied());
+            assert!(solve_part2(&numbers, invalid.unwrap()).is_some());
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Day 10: Adapter Array
+
+use crate::error::number;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use std::collections::HashMap;
+
+pub fn solve_part1(input: &[i32]) -> usize {
+    let mut x: Vec<i32> = input.to_vec();
+    x.push(0);
+
+    x.sort();
+
+    let ones = x
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .filter(|&x| x == 1)
+        .count();
+    let three = x
+        .windows(2)
+        .map(|w| w[1] - w[0])
+        .filter(|&x| x == 3)
+        .count();
+
+    ones * (three + 1)
+}
+
+pub fn solve_part2(input: &[i32]) -> usize {
+    let mut x = input.to_vec();
+    let mut m: HashMap<i32, usize> = HashMap::new();
+
+    x.sort();
+
+    let last = x.pop().unwrap();
+    x.push(last);
+    x.push(last + 3);
+
+    m.insert(0, 1);
+    for i in &x {
+        m.insert(
+            *i,
+            *m.get(&(i - 1)).unwrap_or(&0)
+                + *m.get(&(i - 2)).unwrap_or(&0)
+                + *m.get(&(i - 3)).unwrap_or(&0),
+        );
+    }
+
+    let last = x.iter().last().unwrap();
+
+    *m.get(last).unwrap()
+}
+
+pub fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+    let adapters = input
+        .lines()
+        .map(|line| number(input, line.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if adapters.is_empty() {
+        return Err(ParseError::at_end(input, "no adapters"));
+    }
+
+    Ok(adapters)
+}
+
+/// A bag of `size` adapters, between 1 and 100, one or three jolts apart with at most four
+/// in a row one jolt apart.  More adapters could overflow the number of arrangements.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut jolts = vec![];
+    let (mut jolt, mut ones) = (0, 0);
+    while jolts.len() < size.clamp(1, 100) {
+        if ones < 4 && rng.chance(0.6) {
+            jolt += 1;
+            ones += 1;
+        } else {
+            jolt += 3;
+            ones = 0;
+        }
+        jolts.push(jolt);
+    }
+
+    rng.shuffle(&mut jolts);
+    jolts.iter().map(|jolt| format!("{}\n", jolt)).collect()
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(jolts: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(jolts).into())
+    }
+
+    fn part2(jolts: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(jolts).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE1: &[i32] = &[16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
+    const EXAMPLE2: &[i32] = &[
+        28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8,
+        17, 7, 9, 4, 2, 34, 10, 3,
+    ];
+
+    #[test]
+    fn example1() {
+        assert_eq!(solve_part1(EXAMPLE1), 7 * 5);
+        assert_eq!(solve_part2(EXAMPLE1), 8);
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert_eq!(parse(""), Err(ParseError::new(1, 1, "no adapters")));
+    }
+
+    #[test]
+    fn example2() {
+        assert_eq!(solve_part2(EXAMPLE2), 19208);
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let jolts = parse(&generate(&mut Rng::new(seed), 100)).unwrap();
+            assert_eq!(jolts.len(), 100);
+            assert!(solve_part1(&jolts) > 0);
+            assert!(solve_part2(&jolts) > 0);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Day 11: Seating System
+
+use crate::automaton::{Adjacent, Automaton, LineOfSight, Neighbourhood, Rule, SquareGrid};
+use crate::grid::Grid;
+use crate::observer::{Observer, Silent};
+use crate::random::Rng;
+use crate::repl::Reply;
+use crate::{Answer, ParseError, Solution};
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeatPlan {
+    seats: Grid<char>,
+}
+
+impl SeatPlan {
+    pub fn new(seats: Grid<char>) -> Self {
+        Self { seats }
+    }
+
+    pub fn at(&self, col: i32, row: i32) -> Option<char> {
+        self.seats.get(col as isize, row as isize).copied()
+    }
+
+    pub fn occupied(&self) -> i32 {
+        self.seats.iter().filter(|&c| *c == '#').count() as i32
+    }
+
+    /// Seat people until nobody moves: an empty seat is taken if no neighbour is occupied, an
+    /// occupied seat is left if at least `limit` neighbours are occupied.  `None` if the seating
+    /// comes back to an earlier round and so never settles.
+    fn settle<N>(&self, neighbourhood: N, limit: usize, observer: &mut dyn Observer) -> Option<i32>
+    where
+        N: Neighbourhood<SquareGrid>,
+    {
+        let space = SquareGrid::new(self.seats.map(|&c| c != '.'));
+        let occupied = self.seats.positions().filter(|&p| self.seats[p] == '#');
+        let rule = Rule::new(&[0], &(0..limit).collect::<Vec<_>>());
+
+        let mut automaton = Automaton::new(space, neighbourhood, rule, occupied);
+        let settled = automaton.settle(|a| {
+            let changes = Changes {
+                round: a.generation(),
+                before: a.previous(),
+                after: a.alive(),
+            };
+            observer.event(a.generation(), &changes);
+            let state = Seating(&self.seats, a.alive());
+            observer.step(a.generation(), a.population(), &state)
+        });
+
+        if settled {
+            Some(automaton.population() as i32)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for SeatPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.seats)
+    }
+}
+
+/// The seats of a plan with the occupied ones given separately
+struct Seating<'a>(&'a Grid<char>, &'a HashSet<(usize, usize)>);
+
+impl fmt::Display for Seating<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Seating(seats, occupied) = self;
+        for row in 0..seats.height() {
+            for col in 0..seats.width() {
+                let seat = match seats[(col, row)] {
+                    '.' => '.',
+                    _ if occupied.contains(&(col, row)) => '#',
+                    _ => 'L',
+                };
+                write!(f, "{}", seat)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The seats taken and left in a round, given by the occupied seats before and after it
+pub struct Changes<'a> {
+    pub round: usize,
+    pub before: &'a HashSet<(usize, usize)>,
+    pub after: &'a HashSet<(usize, usize)>,
+}
+
+impl fmt::Display for Changes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the seats in reading order
+        let seats = |seats: Vec<&(usize, usize)>| {
+            let mut seats: Vec<(usize, usize)> = seats.into_iter().map(|&(c, r)| (r, c)).collect();
+            seats.sort_unstable();
+            let list: Vec<String> = seats
+                .iter()
+                .map(|(r, c)| format!("({}, {})", c, r))
+                .collect();
+            match list.len() {
+                0 => "0 seats".to_string(),
+                n => format!("{} seats: {}", n, list.join(", ")),
+            }
+        };
+
+        writeln!(f, "-- Round {} --", self.round)?;
+        writeln!(
+            f,
+            "taken {}",
+            seats(self.after.difference(self.before).collect())
+        )?;
+        write!(
+            f,
+            "left {}",
+            seats(self.before.difference(self.after).collect())
+        )
+    }
+}
+
+pub fn parse(input: &str) -> Result<SeatPlan, ParseError> {
+    Grid::parse(input, "L.#").map(SeatPlan::new)
+}
+
+pub fn solve_part1(input: &SeatPlan) -> Option<i32> {
+    solve_part1_with(input, &mut Silent)
+}
+
+pub fn solve_part1_with(input: &SeatPlan, observer: &mut dyn Observer) -> Option<i32> {
+    input.settle(Adjacent, 4, observer)
+}
+
+pub fn solve_part2(input: &SeatPlan) -> Option<i32> {
+    solve_part2_with(input, &mut Silent)
+}
+
+pub fn solve_part2_with(input: &SeatPlan, observer: &mut dyn Observer) -> Option<i32> {
+    input.settle(LineOfSight, 5, observer)
+}
+
+/// A waiting area of `size` rows of `size` places, at least one, a fifth of them floor.  Some
+/// layouts flip between two seatings forever, those are drawn again.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    loop {
+        let layout: String = (0..size)
+            .map(|_| {
+                let row: String = (0..size)
+                    .map(|_| if rng.chance(0.2) { '.' } else { 'L' })
+                    .collect();
+                row + "\n"
+            })
+            .collect();
+        let plan = parse(&layout).expect("generated layouts parse");
+        if solve_part1(&plan).is_some() && solve_part2(&plan).is_some() {
+            return layout;
+        }
+    }
+}
+
+/// The occupied seats once the seating settles
+fn settled(occupied: Option<i32>) -> Result<Answer, String> {
+    occupied
+        .map(Answer::from)
+        .ok_or_else(|| "the seating never settles".to_string())
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = SeatPlan;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(plan: &Self::Input) -> Result<Answer, String> {
+        settled(solve_part1(plan))
+    }
+
+    fn part2(plan: &Self::Input) -> Result<Answer, String> {
+        settled(solve_part2(plan))
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    const QUERIES: &'static [(&'static str, &'static str)] =
+        &[("show", "The seats before anybody sits down")];
+
+    fn query(plan: &Self::Input, query: &str, _args: &[&str]) -> Option<Reply> {
+        match query {
+            "show" => Some(Ok(plan.to_string())),
+            _ => None,
+        }
+    }
+
+    fn solve_with(
+        plan: &Self::Input,
+        part: u8,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<Answer, String>> {
+        match part {
+            1 => Some(settled(solve_part1_with(plan, observer))),
+            2 => Some(settled(solve_part2_with(plan, observer))),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observer::Trace;
+    const EXAMPLE: &str = "\
+        L.LL.LL.LL
+        LLLLLLL.LL
+        L.L.L..L..
+        LLLL.LL.LL
+        L.LL.LL.LL
+        L.LLLLL.LL
+        ..L.L.....
+        LLLLLLLLLL
+        L.LLLLLL.L
+        L.LLLLL.LL";
+
+    #[test]
+    fn parses_puzzle() {
+        let plan = parse(EXAMPLE).unwrap();
+        assert_eq!(plan.seats.height(), 10);
+        assert_eq!(plan.seats.width(), 10);
+        assert_eq!(plan.occupied(), 0);
+        assert!(Day11::query(&plan, "show", &[])
+            .unwrap()
+            .unwrap()
+            .starts_with("L.LL.LL.LL\nLLLLLLL.LL\n"));
+        assert_eq!(
+            parse("L.L\nLL"),
+            Err(ParseError::new(2, 1, "rows of different length"))
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        let plan = parse(EXAMPLE).unwrap();
+        assert_eq!(plan.at(9, 8), Some('L'));
+        assert_eq!(solve_part1(&plan), Some(37));
+    }
+
+    #[test]
+    fn solves_part2() {
+        let plan = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part2(&plan), Some(26));
+    }
+
+    #[test]
+    fn reports_steps() {
+        let plan = parse(EXAMPLE).unwrap();
+        let mut steps = vec![];
+        let mut observer = |step, population, state: &dyn fmt::Display| {
+            steps.push((step, population, state.to_string()))
+        };
+        solve_part1_with(&plan, &mut observer);
+
+        assert_eq!(steps.len(), 6);
+        assert_eq!(steps[0].1, 71);
+        assert!(steps[0].2.starts_with("#.##.##.##\n"));
+        assert_eq!(steps[5].1, 37);
+    }
+
+    #[test]
+    fn traces_changes() {
+        let plan = parse("LLL\nLLL\n").unwrap();
+        let mut trace = Trace::new(vec![]);
+        solve_part1_with(&plan, &mut trace);
+
+        assert_eq!(
+            String::from_utf8(trace.into_inner()).unwrap(),
+            "\
+-- Round 1 --
+taken 6 seats: (0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)
+left 0 seats
+-- Round 2 --
+taken 0 seats
+left 2 seats: (1, 0), (1, 1)
+-- Round 3 --
+taken 0 seats
+left 0 seats
+"
+        );
+    }
+
+    #[test]
+    fn reports_seating_that_never_settles() {
+        let plan = parse(
+            "\
+LL..LL.L
+LLL.LLLL
+LLLL.LL.
+.LLLL.LL
+.LLLL.LL
+LLLLLLLL
+LLLLL.LL
+.LLLL..L
+LL..LLLL
+LL.L.LLL
+",
+        )
+        .unwrap();
+        assert_eq!(solve_part1(&plan), None);
+        assert_eq!(
+            Day11::part1(&plan),
+            Err("the seating never settles".to_string())
+        );
+    }
+
+    #[test]
+    fn generates_inputs() {
+        // seed 6 draws a layout that never settles first
+        for seed in 0..8 {
+            let plan = parse(&generate(&mut Rng::new(seed), 20)).unwrap();
+            assert!(solve_part1(&plan) > Some(0));
+            assert!(solve_part2(&plan) > Some(0));
+        }
+    }
+}
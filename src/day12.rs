@@ -0,0 +1,257 @@
+//! Day 12: Rain Risk
+
+use crate::error::number;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use regex::Regex;
+
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    let re = Regex::new(
+        r"(?x)
+        ^
+        (?P<cmd>[NSEWLRF])
+        (?P<arg>[[:digit:]]+)
+        $
+        ",
+    )
+    .expect("Invalid regular expression");
+
+    let mut res = vec![];
+    for line in input.lines() {
+        let line = line.trim();
+        let captures = re
+            .captures(line)
+            .ok_or_else(|| ParseError::at(input, line, "expected an action and a value"))?;
+        let cmd = captures["cmd"].chars().next().unwrap();
+        let arg = captures.name("arg").unwrap().as_str();
+        let value = number(input, arg)?;
+
+        if "LR".contains(cmd) && ![90, 180, 270].contains(&value) {
+            return Err(ParseError::at(input, arg, "invalid angle"));
+        }
+
+        res.push((cmd, value));
+    }
+
+    Ok(res)
+}
+
+/// A navigation action and its value
+pub type Instruction = (char, i32);
+
+pub fn solve_part1(input: &[Instruction]) -> i32 {
+    #[derive(Clone, Debug)]
+    struct Ship {
+        lon: i32,
+        lat: i32,
+        heading: i32,
+    }
+
+    let start = Ship {
+        lon: 0,
+        lat: 0,
+        heading: 90,
+    };
+
+    let res = input.iter().fold(start, |cur, &(cmd, arg)| match cmd {
+        'N' => Ship {
+            lat: cur.lat + arg,
+            ..cur
+        },
+        'S' => Ship {
+            lat: cur.lat - arg,
+            ..cur
+        },
+        'E' => Ship {
+            lon: cur.lon - arg,
+            ..cur
+        },
+        'W' => Ship {
+            lon: cur.lon + arg,
+            ..cur
+        },
+        'L' => Ship {
+            heading: (cur.heading - arg + 360) % 360,
+            ..cur
+        },
+        'R' => Ship {
+            heading: (cur.heading + arg + 360) % 360,
+            ..cur
+        },
+        'F' => match cur.heading {
+            0 => Ship {
+                lat: cur.lat + arg,
+                ..cur
+            },
+            90 => Ship {
+                lon: cur.lon + arg,
+                ..cur
+            },
+            180 => Ship {
+                lat: cur.lat - arg,
+                ..cur
+            },
+            270 => Ship {
+                lon: cur.lon - arg,
+                ..cur
+            },
+            _ => panic!("Invalid heading {:?}", cur.heading),
+        },
+        _ => panic!("Invalid instruction"),
+    });
+
+    res.lon.abs() + res.lat.abs()
+}
+
+/// Rotate the vector with the given angle.  Positive angle means counter-clockwise.
+fn rot((x, y): (i32, i32), angle: i32) -> (i32, i32) {
+    let x = x as f32;
+    let y = y as f32;
+    let angle = (angle as f32).to_radians();
+
+    let xp = angle.cos() * x - angle.sin() * y;
+    let yp = angle.sin() * x + angle.cos() * y;
+
+    (xp.round() as i32, yp.round() as i32)
+}
+
+pub fn solve_part2(input: &[Instruction]) -> i32 {
+    struct Ship {
+        lon: i32,
+        lat: i32,
+        dlon: i32,
+        dlat: i32,
+    }
+
+    let start = Ship {
+        lon: 0,
+        lat: 0,
+        dlon: 10,
+        dlat: 1,
+    };
+
+    let res = input.iter().fold(start, |cur, &(cmd, arg)| match cmd {
+        'N' => Ship {
+            dlat: cur.dlat + arg,
+            ..cur
+        },
+        'S' => Ship {
+            dlat: cur.dlat - arg,
+            ..cur
+        },
+        'E' => Ship {
+            dlon: cur.dlon + arg,
+            ..cur
+        },
+        'W' => Ship {
+            dlon: cur.dlon - arg,
+            ..cur
+        },
+
+        'L' => {
+            let (x, y) = rot((cur.dlon, cur.dlat), arg);
+            Ship {
+                dlon: x,
+                dlat: y,
+                ..cur
+            }
+        }
+        'R' => {
+            let (x, y) = rot((cur.dlon, cur.dlat), -arg);
+            Ship {
+                dlon: x,
+                dlat: y,
+                ..cur
+            }
+        }
+        'F' => Ship {
+            lon: cur.lon + cur.dlon * arg,
+            lat: cur.lat + cur.dlat * arg,
+            ..cur
+        },
+        _ => panic!("Invalid instruction"),
+    });
+
+    res.lon.abs() + res.lat.abs()
+}
+
+/// `size` navigation instructions
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let (action, value) = match rng.below(10) {
+                0..=2 => ('F', rng.between(1, 100)),
+                3 | 4 => (*rng.choose(&['L', 'R']), *rng.choose(&[90, 180, 270])),
+                _ => (*rng.choose(&['N', 'S', 'E', 'W']), rng.between(1, 5)),
+            };
+            format!("{}{}\n", action, value)
+        })
+        .collect()
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(instructions: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(instructions).into())
+    }
+
+    fn part2(instructions: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(instructions).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const EXAMPLE: &str = "\
+        F10
+        N3
+        F7
+        R90
+        F11\
+    ";
+
+    #[test]
+    fn parses_puzzle() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(input.len(), 5);
+        assert_eq!(input[3], ('R', 90));
+        assert_eq!(
+            parse("F10\nR45"),
+            Err(ParseError::new(2, 2, "invalid angle"))
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&input), 25);
+    }
+
+    #[test]
+    fn solves_part2() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part2(&input), 286);
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let instructions = parse(&generate(&mut Rng::new(seed), 100)).unwrap();
+            assert_eq!(instructions.len(), 100);
+            solve_part1(&instructions);
+            solve_part2(&instructions);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Day 13: Shuttle Search
+
+use crate::error::number;
+use crate::modular::crt;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+
+pub fn parse(input: &str) -> Result<(i32, Vec<Option<i32>>), ParseError> {
+    let mut lines = input.lines().map(|line| line.trim());
+    let missing = || ParseError::at_end(input, "missing line");
+
+    let departure_time = number(input, lines.next().ok_or_else(missing)?)?;
+    let line = lines.next().ok_or_else(missing)?;
+    let buses: Vec<Option<i32>> = line
+        .split(',')
+        .map(|x| match x {
+            "x" => Ok(None),
+            x => match number(input, x)? {
+                bus if bus > 0 => Ok(Some(bus)),
+                _ => Err(ParseError::at(input, x, "invalid bus ID")),
+            },
+        })
+        .collect::<Result<_, _>>()?;
+
+    if buses.iter().all(Option::is_none) {
+        return Err(ParseError::at(input, line, "no bus in service"));
+    }
+
+    Ok((departure_time, buses))
+}
+
+pub fn solve_part1((departure, buses): &(i32, Vec<Option<i32>>)) -> i32 {
+    for t in *departure.. {
+        for b in buses.iter().filter_map(|x| *x) {
+            if t % b == 0 {
+                return (t - departure) * b;
+            }
+        }
+    }
+
+    0
+}
+
+/// The earliest timestamp the buses depart one after the other, `None` if no timestamp fits
+/// every bus, as with IDs sharing a factor
+pub fn solve_part2(buses: &[Option<i32>]) -> Option<i64> {
+    // the bus at offset `i` departs at `t + i`, so t ≡ -i (mod bus)
+    let congruences: Vec<_> = buses
+        .iter()
+        .enumerate()
+        .filter_map(|(i, bus)| bus.map(|bus| (-(i as i128), bus as i128)))
+        .collect();
+
+    let (t, _) = crt(&congruences)?;
+    Some(t as i64)
+}
+
+/// A departure time and `size` bus slots, at least one, the first always in service.  The bus
+/// IDs are distinct primes whose product, the period of the timestamps in part 2, fits in an
+/// `i64`.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut primes: Vec<i64> = (7..1000)
+        .filter(|&n| (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0))
+        .collect();
+    rng.shuffle(&mut primes);
+
+    let mut product: i64 = 1;
+    let mut primes = primes.into_iter();
+    let buses: Vec<String> = (0..size.max(1))
+        .map(|slot| match primes.next() {
+            Some(bus) if (slot == 0 || rng.chance(0.3)) && product < i64::MAX / 2 / bus => {
+                product *= bus;
+                bus.to_string()
+            }
+            _ => "x".to_string(),
+        })
+        .collect();
+
+    format!("{}\n{}\n", rng.between(1, 1_000_000), buses.join(","))
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = (i32, Vec<Option<i32>>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(notes: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(notes).into())
+    }
+
+    fn part2(notes: &Self::Input) -> Result<Answer, String> {
+        solve_part2(&notes.1)
+            .map(Answer::from)
+            .ok_or_else(|| "no timestamp fits every bus".to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const EXAMPLE: &str = "\
+        939
+        7,13,x,x,59,x,31,19\
+    ";
+
+    #[test]
+    fn parses_puzzle() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            input,
+            (
+                939,
+                vec![
+                    Some(7),
+                    Some(13),
+                    None,
+                    None,
+                    Some(59),
+                    None,
+                    Some(31),
+                    Some(19)
+                ]
+            )
+        );
+        assert_eq!(
+            parse("939\n7,0,x"),
+            Err(ParseError::new(2, 3, "invalid bus ID"))
+        );
+        assert_eq!(
+            parse("939\nx,x\n"),
+            Err(ParseError::new(2, 1, "no bus in service"))
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&input), 295);
+    }
+
+    #[test]
+    fn solves_part2() {
+        let (_, schedule) = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part2(&schedule), Some(1068781));
+        assert_eq!(
+            solve_part2(&[Some(17), None, Some(13), Some(19)]),
+            Some(3417)
+        );
+    }
+
+    #[test]
+    fn finds_no_timestamp() {
+        // departing at an even timestamp and at an odd one right after it
+        let notes = parse("939\n4,6\n").unwrap();
+        assert_eq!(solve_part2(&notes.1), None);
+        assert_eq!(
+            Day13::solve(&notes, 2),
+            Some(Err("no timestamp fits every bus".to_string()))
+        );
+        assert_eq!(solve_part2(&[Some(4), None, Some(6)]), Some(4));
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let notes = parse(&generate(&mut Rng::new(seed), 30)).unwrap();
+            assert_eq!(notes.1.len(), 30);
+            assert!(notes.1[0].is_some());
+
+            let t = solve_part2(&notes.1).unwrap();
+            for (i, bus) in notes.1.iter().enumerate() {
+                if let Some(bus) = bus {
+                    assert_eq!((t + i as i64) % *bus as i64, 0);
+                }
+            }
+            solve_part1(&notes);
+        }
+    }
+}
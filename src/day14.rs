@@ -0,0 +1,299 @@
+//! Day 14: Docking Data
+
+use crate::error::{number, only_chars};
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use itertools::Itertools;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::default::Default;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    Mask(Mask),
+    Memset(u64, u64),
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub struct Mask {
+    enabled: u64,
+    mask: u64,
+}
+
+impl Mask {
+    pub fn new(op_arg: &str) -> Self {
+        let mask_value = op_arg
+            .chars()
+            .enumerate()
+            .map(|(i, v)| match v {
+                '1' => 2_u64.pow(35 - (i as u32)),
+                '0' => 0,
+                'X' => 0,
+                _ => 0,
+            })
+            .sum::<u64>();
+
+        let mask_range = op_arg
+            .chars()
+            .enumerate()
+            .map(|(i, v)| {
+                if v != 'X' {
+                    2_u64.pow(35 - (i as u32))
+                } else {
+                    0
+                }
+            })
+            .sum::<u64>();
+
+        Self {
+            enabled: mask_range,
+            mask: mask_value,
+        }
+    }
+
+    pub fn apply(&self, input: u64) -> u64 {
+        let mut res = input;
+
+        for i in 0..64 {
+            let flag = 1 << i;
+            if (flag & self.enabled) > 0 {
+                if ((res & flag) > 0) && (self.mask & flag == 0) {
+                    res &= !flag; // force to 0
+                } else {
+                    res |= self.mask & flag; // force to 1
+                }
+            }
+        }
+
+        res
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Op>, ParseError> {
+    let re_mem_cmd = Regex::new(r"^mem\[(?P<index>[[:digit:]]+)\]$").unwrap();
+
+    let mut res = vec![];
+    for line in input.lines() {
+        let line = line.trim();
+        let (op_name, op_arg) = line
+            .split_once(" = ")
+            .ok_or_else(|| ParseError::at(input, line, "expected \"<op> = <value>\""))?;
+
+        if op_name == "mask" {
+            only_chars(input, op_arg, "01X")?;
+            if op_arg.len() != 36 {
+                return Err(ParseError::at(input, op_arg, "mask must have 36 bits"));
+            }
+            res.push(Op::Mask(Mask::new(op_arg)));
+        } else {
+            let captures = re_mem_cmd
+                .captures(op_name)
+                .ok_or_else(|| ParseError::at(input, op_name, "unknown operation"))?;
+            if res.is_empty() {
+                return Err(ParseError::at(
+                    input,
+                    line,
+                    "expected a mask before any write",
+                ));
+            }
+            let index = number(input, captures.name("index").unwrap().as_str())?;
+            let val = number(input, op_arg)?;
+            res.push(Op::Memset(index, val));
+        }
+    }
+
+    Ok(res)
+}
+
+pub fn solve_part1(input: &[Op]) -> u64 {
+    let mut mask: Mask = Default::default();
+    let mut memory: HashMap<u64, u64> = HashMap::new();
+
+    for op in input {
+        match op {
+            Op::Memset(index, value) => {
+                memory.insert(*index, mask.apply(*value));
+            }
+            Op::Mask(new_mask) => mask = *new_mask,
+        }
+    }
+
+    memory.values().copied().sum()
+}
+
+/// All the memory addresses written by the version 2 decoder
+pub fn decode(address: u64, mask: &Mask) -> HashSet<u64> {
+    let mut address = address;
+    let mut res: HashSet<u64> = HashSet::new();
+
+    // If the bitmask bit is 1, the corresponding memory address bit is overwritten with 1.
+    for i in 0..64 {
+        address |= mask.mask & (1 << i);
+    }
+
+    let it = (0..36)
+        .map(|i| 1 << i)
+        .filter(|flag| mask.enabled & flag == 0)
+        .map(|flag| vec![(false, flag), (true, flag)])
+        .multi_cartesian_product();
+
+    for variant in it {
+        let mut x = address;
+        for (b, flag) in variant {
+            match b {
+                true => x |= flag,   // force to 1
+                false => x &= !flag, // force to 0
+            }
+            res.insert(x);
+        }
+    }
+
+    res
+}
+
+pub fn solve_part2(input: &[Op]) -> u64 {
+    let mut mask: Mask = Default::default();
+    let mut memory: HashMap<u64, u64> = HashMap::new();
+
+    for op in input {
+        match op {
+            Op::Memset(address, value) => {
+                for a in decode(*address, &mask) {
+                    memory.insert(a, *value);
+                }
+            }
+            Op::Mask(new_mask) => mask = *new_mask,
+        }
+    }
+
+    memory.values().copied().sum()
+}
+
+/// A program of `size` writes to memory, with a new mask every one to five writes.  A mask
+/// has at most nine floating bits, so a write in part 2 touches at most 512 addresses.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut program = String::new();
+    let mut writes = 0;
+    while writes < size {
+        let count = rng.below(10);
+        let floating = rng.sample(&(0..36).collect::<Vec<_>>(), count);
+        let mask: String = (0..36)
+            .map(|bit| match bit {
+                _ if floating.contains(&bit) => 'X',
+                _ if rng.chance(0.5) => '1',
+                _ => '0',
+            })
+            .collect();
+        program += &format!("mask = {}\n", mask);
+
+        for _ in 0..rng.between(1, 5).min((size - writes) as i64) {
+            let address = rng.between(0, 65535);
+            let value = rng.between(0, (1 << 36) - 1);
+            program += &format!("mem[{}] = {}\n", address, value);
+            writes += 1;
+        }
+    }
+    program
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Op>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(program: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(program).into())
+    }
+
+    fn part2(program: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(program).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const EXAMPLE: &str = "\
+        mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X
+        mem[8] = 11
+        mem[7] = 101
+        mem[8] = 0\
+    ";
+
+    #[test]
+    fn parses_puzzle() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            input,
+            vec![
+                Op::Mask(Mask::new("XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X")),
+                Op::Memset(8, 11),
+                Op::Memset(7, 101),
+                Op::Memset(8, 0)
+            ]
+        );
+        assert_eq!(
+            parse("mask = X1\nmem[8] = 11"),
+            Err(ParseError::new(1, 8, "mask must have 36 bits"))
+        );
+        assert_eq!(
+            parse("mem[8] = 11\nmask = X"),
+            Err(ParseError::new(1, 1, "expected a mask before any write"))
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let mask = Mask::new("XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X");
+        assert_eq!(mask.apply(11), 73);
+        assert_eq!(mask.apply(101), 101);
+        assert_eq!(mask.apply(0), 64);
+    }
+
+    #[test]
+    fn solves_part1() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&input), 165);
+    }
+
+    #[test]
+    fn test_decoder() {
+        let mask = Mask::new("000000000000000000000000000000X1001X");
+        assert_eq!(
+            decode(42, &mask),
+            [26, 27, 58, 59].iter().cloned().collect::<HashSet<u64>>()
+        );
+    }
+
+    #[test]
+    fn solves_part2() {
+        let p: &str = "\
+            mask = 000000000000000000000000000000X1001X
+            mem[42] = 100
+            mask = 00000000000000000000000000000000X0XX
+            mem[26] = 1\
+        ";
+
+        let input = parse(p).unwrap();
+        assert_eq!(solve_part2(&input), 208);
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let program = parse(&generate(&mut Rng::new(seed), 50)).unwrap();
+            let writes = program.iter().filter(|op| matches!(op, Op::Memset(..)));
+            assert_eq!(writes.count(), 50);
+            assert!(solve_part1(&program) > 0);
+            assert!(solve_part2(&program) > 0);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Day 15: Rambunctious Recitation
+
+use crate::differential::{compare, Disagreement};
+use crate::error::number;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use std::collections::HashMap;
+
+pub fn last_number_v1(starting: &[usize], end: usize) -> usize {
+    let mut numbers: Vec<usize> = vec![];
+
+    numbers.extend_from_slice(starting);
+    numbers.reserve(end);
+
+    while numbers.len() < end {
+        let last = *numbers.last().unwrap();
+
+        let say = match numbers.iter().rev().skip(1).position(|&x| x == last) {
+            Some(diff) => diff + 1,
+            None => 0,
+        };
+
+        numbers.push(say);
+    }
+
+    *numbers.last().unwrap()
+}
+
+pub fn last_number_v2(starting: &[usize], end: usize) -> usize {
+    let mut mem: HashMap<usize, Vec<usize>> = HashMap::new();
+
+    let mut turn = 0;
+    let mut prev = 0;
+    for n in starting.iter() {
+        prev = *n;
+        mem.insert(*n, vec![turn]);
+        turn += 1;
+    }
+
+    while turn < end {
+        let say = mem
+            .get(&prev)
+            .map(|v| if v.len() == 1 { 0 } else { v[0] - v[1] })
+            .unwrap();
+
+        match mem.get_mut(&say) {
+            Some(v) => {
+                v.insert(0, turn);
+                v.truncate(2);
+            }
+            None => {
+                mem.insert(say, vec![turn]);
+            }
+        }
+
+        prev = say;
+        turn += 1;
+    }
+
+    prev
+}
+
+pub fn solve_part1(starting: &[usize]) -> usize {
+    last_number_v1(starting, 2020)
+}
+
+pub fn solve_part2(starting: &[usize]) -> usize {
+    last_number_v2(starting, 30000000)
+}
+
+/// The comma separated starting numbers
+pub fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    input
+        .trim()
+        .split(',')
+        .map(|x| number(input, x.trim()))
+        .collect()
+}
+
+/// `size` distinct starting numbers, between 1 and 20
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.clamp(1, 20);
+    let numbers: Vec<usize> = (0..2 * size).collect();
+    let starting: Vec<String> = rng
+        .sample(&numbers, size)
+        .iter()
+        .map(|n| n.to_string())
+        .collect();
+    starting.join(",") + "\n"
+}
+
+/// The starting numbers and the number of turns
+type Game = (Vec<usize>, usize);
+
+/// A game of one to six distinct starting numbers below 10, played for up to 200 turns
+fn game(rng: &mut Rng) -> Game {
+    let numbers: Vec<usize> = (0..10).collect();
+    let count = rng.between(1, 6) as usize;
+    let starting = rng.sample(&numbers, count);
+    let end = rng.between(count as i64, 200) as usize;
+    (starting, end)
+}
+
+/// The games with fewer turns, fewer starting numbers or a smaller starting number
+fn simpler((starting, end): &Game) -> Vec<Game> {
+    let mut games = vec![];
+
+    if *end > starting.len() {
+        games.push((starting.clone(), (*end / 2).max(starting.len())));
+        games.push((starting.clone(), end - 1));
+    }
+    for i in 0..starting.len() {
+        if starting.len() > 1 {
+            let mut fewer = starting.clone();
+            fewer.remove(i);
+            games.push((fewer, *end));
+        }
+        if let Some(n) = (0..starting[i]).find(|n| !starting.contains(n)) {
+            let mut smaller = starting.clone();
+            smaller[i] = n;
+            games.push((smaller, *end));
+        }
+    }
+
+    games
+}
+
+/// Compare `last_number_v1` and `last_number_v2` on `cases` random games
+pub fn compare_versions(rng: &mut Rng, cases: usize) -> Option<Disagreement<Game, usize>> {
+    compare(
+        rng,
+        cases,
+        game,
+        simpler,
+        |(starting, end)| last_number_v1(starting, *end),
+        |(starting, end)| last_number_v2(starting, *end),
+    )
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(starting: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(starting).into())
+    }
+
+    fn part2(starting: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(starting).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_puzzle() {
+        assert_eq!(parse("0,3,6\n"), Ok(vec![0, 3, 6]));
+        assert_eq!(
+            parse("0,,6"),
+            Err(ParseError::new(1, 3, "invalid number \"\""))
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        assert_eq!(solve_part1(&[0, 3, 6]), 436);
+        assert_eq!(solve_part1(&[1, 3, 2]), 1);
+        assert_eq!(solve_part1(&[3, 1, 2]), 1836);
+    }
+
+    #[test]
+    fn solves_part2() {
+        assert_eq!(last_number_v2(&[0, 3, 6], 2020), 436);
+        assert_eq!(last_number_v2(&[1, 3, 2], 2020), 1);
+        assert_eq!(last_number_v2(&[3, 1, 2], 2020), 1836);
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let starting = parse(&generate(&mut Rng::new(seed), 6)).unwrap();
+            assert_eq!(starting.len(), 6);
+            assert_eq!(solve_part1(&starting), last_number_v2(&starting, 2020));
+        }
+    }
+
+    #[test]
+    fn shrinks_games() {
+        let games = simpler(&(vec![3, 0], 5));
+        assert_eq!(
+            games,
+            [
+                (vec![3, 0], 2),
+                (vec![3, 0], 4),
+                (vec![0], 5),
+                (vec![1, 0], 5),
+                (vec![3], 5)
+            ]
+        );
+        assert_eq!(compare_versions(&mut Rng::new(1), 100), None);
+    }
+}
@@ -0,0 +1,370 @@
+//! Day 16: Ticket Translation
+
+use crate::error::number;
+use crate::input::paragraphs;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use itertools::Itertools;
+use regex::Regex;
+use std::collections::HashSet;
+
+#[derive(Debug)]
+pub struct Input {
+    pub fields: Vec<(String, Vec<(usize, usize)>)>,
+    pub my_ticket: Vec<usize>,
+    pub nearby_tickets: Vec<Vec<usize>>,
+}
+
+/// The comma separated values of a ticket, which must have one value per field
+fn parse_ticket(input: &str, line: &str, fields: usize) -> Result<Vec<usize>, ParseError> {
+    let values = line
+        .trim()
+        .split(',')
+        .map(|x| number(input, x))
+        .collect::<Result<Vec<usize>, _>>()?;
+
+    if values.len() != fields {
+        let reason = format!("expected {} values, found {}", fields, values.len());
+        return Err(ParseError::at(input, line.trim(), reason));
+    }
+
+    Ok(values)
+}
+
+/// The lines of a section following its header
+fn section<'a>(
+    input: &'a str,
+    section: Option<&'a str>,
+    header: &str,
+) -> Result<impl Iterator<Item = &'a str>, ParseError> {
+    let section = section
+        .ok_or_else(|| ParseError::at_end(input, format!("missing section {:?}", header)))?;
+    let mut lines = section.lines().map(|line| line.trim());
+
+    match lines.next() {
+        Some(line) if line == header => Ok(lines),
+        _ => Err(ParseError::at(
+            input,
+            section.trim_start(),
+            format!("expected {:?}", header),
+        )),
+    }
+}
+
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let mut fields: Vec<(String, Vec<(usize, usize)>)> = vec![];
+
+    let mut sections = paragraphs(input);
+
+    let fields_section = sections.next().unwrap_or_default();
+
+    let re_field = Regex::new(
+        r"^(?P<name>[^:]+): (?P<low1>[[:digit:]]+)-(?P<high1>[[:digit:]]+) or (?P<low2>[[:digit:]]+)-(?P<high2>[[:digit:]]+)$",
+    )
+    .unwrap();
+    for line in fields_section.lines().map(|line| line.trim()) {
+        let captures = re_field.captures(line).ok_or_else(|| {
+            ParseError::at(
+                input,
+                line,
+                "expected \"<field>: <low>-<high> or <low>-<high>\"",
+            )
+        })?;
+        let range = |low, high| -> Result<(usize, usize), ParseError> {
+            Ok((
+                number(input, captures.name(low).unwrap().as_str())?,
+                number(input, captures.name(high).unwrap().as_str())?,
+            ))
+        };
+
+        let name = captures["name"].to_string();
+        let values = vec![range("low1", "high1")?, range("low2", "high2")?];
+        fields.push((name, values));
+    }
+
+    let mut mine = section(input, sections.next(), "your ticket:")?;
+    let my_ticket = match mine.next() {
+        Some(line) => parse_ticket(input, line, fields.len())?,
+        None => return Err(ParseError::at_end(input, "missing ticket")),
+    };
+
+    let nearby_tickets = section(input, sections.next(), "nearby tickets:")?
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_ticket(input, line, fields.len()))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Input {
+        fields,
+        my_ticket,
+        nearby_tickets,
+    })
+}
+
+/// The values of the ticket which are not valid for any field
+pub fn invalid(ticket: &[usize], fields: &[(String, Vec<(usize, usize)>)]) -> Vec<usize> {
+    let mut res = vec![];
+
+    for t in ticket {
+        let mut ok = false;
+        't: for (_, f) in fields.iter() {
+            for (lo, hi) in f {
+                if t >= lo && t <= hi {
+                    ok = true;
+                    break 't;
+                }
+            }
+        }
+
+        if !ok {
+            res.push(*t)
+        }
+    }
+
+    res
+}
+
+pub fn solve_part1(input: &Input) -> usize {
+    input
+        .nearby_tickets
+        .iter()
+        .map(|ticket| invalid(ticket, &input.fields).iter().sum::<usize>())
+        .sum()
+}
+
+/// The names of the fields and their values on my ticket
+pub fn find_fields(input: &Input) -> Vec<(String, usize)> {
+    let valid_tickets: Vec<_> = input
+        .nearby_tickets
+        .iter()
+        .filter(|t| invalid(t, &input.fields).is_empty())
+        .collect();
+
+    let mut indexes: HashSet<usize> = (0..input.my_ticket.len()).collect();
+    let mut res: Vec<(String, usize)> = vec![];
+
+    for _ in 0..input.my_ticket.len() {
+        for (n, c) in &input.fields {
+            let (lo1, hi1) = c[0];
+            let (lo2, hi2) = c[1];
+
+            let matching: Vec<_> = indexes
+                .iter()
+                .copied()
+                .filter(|i| {
+                    valid_tickets
+                        .iter()
+                        .all(|t| (lo1 <= t[*i] && t[*i] <= hi1) || (lo2 <= t[*i] && t[*i] <= hi2))
+                })
+                .collect();
+
+            if matching.len() == 1 {
+                indexes.remove(&matching[0]);
+                res.push((n.to_string(), input.my_ticket[matching[0]]))
+            }
+        }
+    }
+
+    res
+}
+
+pub fn solve_part2(input: &Input) -> usize {
+    find_fields(input)
+        .iter()
+        .filter_map(|(name, t)| {
+            if name.starts_with("departure") {
+                Some(t)
+            } else {
+                None
+            }
+        })
+        .product()
+}
+
+/// Notes with between 3 and 20 fields and `size` nearby tickets, at least one.  Field `i`
+/// accepts `1-{100i+20} or {100i+30}-{100i+50}`, so the values of its column rule out the
+/// fields before it and the fields can be told apart one by one.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const NAMES: [&str; 20] = [
+        "departure location",
+        "departure station",
+        "departure platform",
+        "departure track",
+        "departure date",
+        "departure time",
+        "arrival location",
+        "arrival station",
+        "arrival platform",
+        "arrival track",
+        "class",
+        "duration",
+        "price",
+        "route",
+        "row",
+        "seat",
+        "train",
+        "type",
+        "wagon",
+        "zone",
+    ];
+
+    let count = rng.between(3, 20) as usize;
+    let names = rng.sample(&NAMES, count);
+    // the field of every column
+    let mut fields: Vec<usize> = (0..count).collect();
+    rng.shuffle(&mut fields);
+
+    // a value accepted by field `band` and the ones after it, but by none before it
+    let value = |rng: &mut Rng, band: usize| {
+        let offset = rng.between(1, 41) as usize;
+        100 * band + if offset <= 20 { offset } else { offset + 9 }
+    };
+    let render = |ticket: Vec<usize>| ticket.iter().join(",") + "\n";
+
+    let mine = render(fields.iter().map(|_| value(rng, 0)).collect());
+    // one ticket holds a value of its own band in every column, pinning the fields down
+    let pinned = rng.below(size.max(1));
+    let nearby: String = (0..size.max(1))
+        .map(|t| {
+            let mut ticket: Vec<usize> = fields
+                .iter()
+                .map(|&field| {
+                    let band = if t == pinned {
+                        field
+                    } else {
+                        rng.below(field + 1)
+                    };
+                    value(rng, band)
+                })
+                .collect();
+            if t != pinned && rng.chance(0.2) {
+                ticket[rng.below(count)] = 100 * count + rng.between(1, 999) as usize;
+            }
+            render(ticket)
+        })
+        .collect();
+
+    let mut rules: Vec<String> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let (a, b, c) = (100 * i + 20, 100 * i + 30, 100 * i + 50);
+            format!("{}: 1-{} or {}-{}\n", name, a, b, c)
+        })
+        .collect();
+    rng.shuffle(&mut rules);
+
+    format!(
+        "{}\nyour ticket:\n{}\nnearby tickets:\n{}",
+        rules.concat(),
+        mine,
+        nearby
+    )
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(notes: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(notes).into())
+    }
+
+    fn part2(notes: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(notes).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const EXAMPLE1: &str = "\
+        class: 1-3 or 5-7
+        row: 6-11 or 33-44
+        seat: 13-40 or 45-50
+
+        your ticket:
+        7,1,14
+
+        nearby tickets:
+        7,3,47
+        40,4,50
+        55,2,20
+        38,6,12\
+    ";
+
+    const EXAMPLE2: &str = "\
+        class: 0-1 or 4-19
+        row: 0-5 or 8-19
+        seat: 0-13 or 16-19
+
+        your ticket:
+        11,12,13
+
+        nearby tickets:
+        3,9,18
+        15,1,5
+        5,14,9\
+    ";
+
+    #[test]
+    fn parses_puzzle() {
+        let input = parse(EXAMPLE1).unwrap();
+        assert_eq!(input.fields.len(), 3);
+        assert_eq!(input.my_ticket.len(), 3);
+        assert_eq!(input.nearby_tickets.len(), 4);
+
+        assert_eq!(
+            parse(&EXAMPLE1.replace("40,4,50", "40,4")).unwrap_err(),
+            ParseError::new(10, 9, "expected 3 values, found 2")
+        );
+
+        let input = parse(&EXAMPLE1.replace('\n', " \r\n")).unwrap();
+        assert_eq!(input.nearby_tickets.len(), 4);
+    }
+
+    #[test]
+    fn solves_part1() {
+        let input = parse(EXAMPLE1).unwrap();
+        assert_eq!(solve_part1(&input), 71);
+    }
+
+    #[test]
+    fn identifies_fields() {
+        let input = parse(EXAMPLE2).unwrap();
+
+        assert_eq!(
+            find_fields(&input),
+            vec![
+                ("seat".to_string(), 13),
+                ("class".to_string(), 12),
+                ("row".to_string(), 11),
+            ]
+        );
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let notes = parse(&generate(&mut Rng::new(seed), 50)).unwrap();
+            assert_eq!(notes.nearby_tickets.len(), 50);
+
+            let mut found: Vec<String> = find_fields(&notes).into_iter().map(|(n, _)| n).collect();
+            found.sort();
+            let mut names: Vec<String> = notes.fields.iter().map(|(n, _)| n.clone()).collect();
+            names.sort();
+            assert_eq!(found, names);
+
+            solve_part1(&notes);
+            assert!(solve_part2(&notes) > 0);
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//! Day 17: Conway Cubes
+
+use crate::automaton::{Adjacent, Automaton, Lattice, Rule, Topology};
+use crate::error::only_chars;
+use crate::observer::{Observer, Silent};
+use crate::random::Rng;
+use crate::repl::Reply;
+use crate::{Answer, ParseError, Solution};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+pub type Coords = Vec<i32>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cube {
+    grid: HashSet<Coords>,
+    dim: usize,
+}
+
+impl Cube {
+    pub fn new(input: &str, dim: usize) -> Self {
+        let mut grid: HashSet<Coords> = HashSet::new();
+
+        for (row, line) in input.lines().enumerate() {
+            let line = line.trim();
+
+            for (col, c) in line.chars().enumerate() {
+                if c == '#' {
+                    let mut elem = vec![0; dim];
+                    elem[0] = col as i32;
+                    elem[1] = row as i32;
+                    grid.insert(elem);
+                }
+            }
+        }
+
+        Self { grid, dim }
+    }
+
+    /// The same cubes embedded in `dim` dimensions
+    pub fn with_dimensions(&self, dim: usize) -> Self {
+        let grid = self
+            .grid
+            .iter()
+            .map(|c| {
+                let mut c = c.clone();
+                c.resize(dim, 0);
+                c
+            })
+            .collect();
+
+        Self { grid, dim }
+    }
+
+    pub fn occupied(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// The boot process: an inactive cube with three active neighbours becomes active, an
+    /// active cube stays active with two or three active neighbours
+    pub fn automaton(&self) -> Automaton<Lattice, Adjacent> {
+        let rule = Rule::new(&[3], &[2, 3]);
+        Automaton::new(Lattice::new(self.dim), Adjacent, rule, self.grid.clone())
+    }
+
+    pub fn step1(&mut self) {
+        let mut automaton = self.automaton();
+        automaton.step();
+        self.grid = automaton.alive().clone();
+    }
+}
+
+/// The `x`-`y` slices with active cubes, headed by their coordinates in the other dimensions
+impl fmt::Display for Cube {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bounds = |d: usize| {
+            let values = self.grid.iter().map(|c| c[d]);
+            values.clone().min().unwrap_or(0)..=values.max().unwrap_or(-1)
+        };
+        let layers: BTreeSet<&[i32]> = self.grid.iter().map(|c| &c[2..]).collect();
+
+        for (i, layer) in layers.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            if !layer.is_empty() {
+                let names = ["z", "w"];
+                let coords = layer.iter().enumerate().map(|(d, v)| match names.get(d) {
+                    Some(name) => format!("{}={}", name, v),
+                    None => format!("x{}={}", d + 2, v),
+                });
+                writeln!(f, "{}", coords.collect::<Vec<_>>().join(", "))?;
+            }
+            for y in bounds(1) {
+                for x in bounds(0) {
+                    let cube: Coords = [x, y].iter().chain(layer).copied().collect();
+                    write!(f, "{}", if self.grid.contains(&cube) { '#' } else { '.' })?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Return the neighbours' coordinates
+pub fn neighbours(cell: &[i32]) -> Vec<Coords> {
+    let lattice = Lattice::new(cell.len());
+    let cell = cell.to_vec();
+    lattice
+        .directions()
+        .iter()
+        .filter_map(|d| lattice.next(&cell, d))
+        .collect()
+}
+
+/// The active cubes after the six cycles of the boot process
+pub fn solve(input: &Cube, observer: &mut dyn Observer) -> usize {
+    let mut automaton = input.automaton();
+    automaton.run(6, |a| {
+        let cube = Cube {
+            grid: a.alive().clone(),
+            dim: input.dim,
+        };
+        observer.step(a.generation(), a.population(), &cube)
+    });
+
+    automaton.population()
+}
+
+/// The initial two-dimensional slice
+pub fn parse(input: &str) -> Result<Cube, ParseError> {
+    for line in input.lines() {
+        only_chars(input, line.trim(), ".#")?;
+    }
+
+    Ok(Cube::new(input, 2))
+}
+
+pub fn solve_part1(input: &Cube) -> usize {
+    solve(&input.with_dimensions(3), &mut Silent)
+}
+
+pub fn solve_part2(input: &Cube) -> usize {
+    solve(&input.with_dimensions(4), &mut Silent)
+}
+
+/// A slice of `size` by `size` cubes, at least one, about a third of them active
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    (0..size)
+        .map(|_| {
+            let row: String = (0..size)
+                .map(|_| if rng.chance(0.35) { '#' } else { '.' })
+                .collect();
+            row + "\n"
+        })
+        .collect()
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Cube;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(slice: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(slice).into())
+    }
+
+    fn part2(slice: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(slice).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    const QUERIES: &'static [(&'static str, &'static str)] =
+        &[("show", "The active cubes before the first cycle")];
+
+    fn query(cube: &Self::Input, query: &str, _args: &[&str]) -> Option<Reply> {
+        match query {
+            "show" => Some(Ok(cube.to_string())),
+            _ => None,
+        }
+    }
+
+    fn solve_with(
+        slice: &Self::Input,
+        part: u8,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<Answer, String>> {
+        match part {
+            1 => Some(Ok(solve(&slice.with_dimensions(3), observer).into())),
+            2 => Some(Ok(solve(&slice.with_dimensions(4), observer).into())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const EXAMPLE: &str = "\
+        .#.
+        ..#
+        ###\
+        ";
+
+    #[test]
+    fn parses_puzzle() {
+        let plan = Cube::new(EXAMPLE, 3);
+        assert_eq!(plan.occupied(), 5);
+        assert_eq!(parse(EXAMPLE).map(|slice| slice.occupied()), Ok(5));
+        assert_eq!(
+            parse(".#.\n.L#"),
+            Err(ParseError::new(2, 2, "unexpected character 'L'"))
+        );
+    }
+
+    #[test]
+    fn displays_slices() {
+        let mut cube = Cube::new(EXAMPLE, 3);
+        assert_eq!(cube.to_string(), "z=0\n.#.\n..#\n###\n");
+        assert_eq!(
+            Day17::query(&parse(EXAMPLE).unwrap(), "show", &[]),
+            Some(Ok(".#.\n..#\n###\n".to_string()))
+        );
+
+        cube.step1();
+        assert_eq!(
+            cube.to_string(),
+            "z=-1\n#..\n..#\n.#.\n\nz=0\n#.#\n.##\n.#.\n\nz=1\n#..\n..#\n.#.\n"
+        );
+    }
+
+    #[test]
+    fn number_of_neigbours() {
+        assert_eq!(neighbours(&[0, 0, 0]).len(), 26);
+        assert_eq!(neighbours(&[0, 0, 0, 0]).len(), 80);
+    }
+
+    #[test]
+    fn solves_part1() {
+        let plan = Cube::new(EXAMPLE, 3);
+        assert_eq!(solve(&plan, &mut Silent), 112);
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..3 {
+            let slice = parse(&generate(&mut Rng::new(seed), 4)).unwrap();
+            solve_part1(&slice);
+        }
+    }
+}
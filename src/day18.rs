@@ -0,0 +1,454 @@
+//! Day 18: Operation Order
+
+use crate::differential::{compare, Disagreement};
+use crate::random::Rng;
+use crate::repl::{arg, Reply};
+use crate::{Answer, ParseError, Solution};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Exp {
+    Add(Box<Exp>, Box<Exp>),
+    Mul(Box<Exp>, Box<Exp>),
+    Number(u64),
+}
+
+impl Exp {
+    /// Parse the expression with equal precedence for `+` and `*`
+    pub fn v1(input: &str) -> Self {
+        let rpn = rpn(input, 1);
+        ast(&rpn)
+    }
+
+    /// Parse the expression with `+` evaluated before `*`
+    pub fn v2(input: &str) -> Self {
+        let rpn = rpn(input, 2);
+        ast(&rpn)
+    }
+
+    /// The text with the fewest parentheses which parses back into the expression, with equal
+    /// precedence for `+` and `*` in part 1 and `+` first in part 2
+    pub fn format(&self, part: u8) -> String {
+        let (a, b, op) = match self {
+            Exp::Number(n) => return n.to_string(),
+            Exp::Add(a, b) => (a, b, '+'),
+            Exp::Mul(a, b) => (a, b, '*'),
+        };
+
+        // both operators are left-associative, only `+` binds a product on its left in part 2
+        let wrap_left = part == 2 && op == '+' && matches!(**a, Exp::Mul(..));
+        let wrap_right = match **b {
+            Exp::Number(_) => false,
+            Exp::Add(..) => part == 1 || op == '+',
+            Exp::Mul(..) => true,
+        };
+        let wrap = |e: &Exp, wrap| {
+            if wrap {
+                format!("({})", e.format(part))
+            } else {
+                e.format(part)
+            }
+        };
+
+        format!("{} {} {}", wrap(a, wrap_left), op, wrap(b, wrap_right))
+    }
+
+    pub fn eval(&self) -> u64 {
+        match self {
+            Exp::Number(n) => *n,
+            Exp::Add(a, b) => a.eval() + b.eval(),
+            Exp::Mul(a, b) => a.eval() * b.eval(),
+        }
+    }
+}
+
+/// Every operation in parentheses but the outermost, showing the order of evaluation
+impl fmt::Display for Exp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a, b, op) = match self {
+            Exp::Number(n) => return write!(f, "{}", n),
+            Exp::Add(a, b) => (a, b, '+'),
+            Exp::Mul(a, b) => (a, b, '*'),
+        };
+
+        for (i, e) in [a, b].iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", op)?;
+            }
+            match ***e {
+                Exp::Number(n) => write!(f, "{}", n)?,
+                _ => write!(f, "({})", e)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How both parts evaluate the expression, with its operations in parentheses
+fn evaluate(exp: &str) -> String {
+    [Exp::v1(exp), Exp::v2(exp)]
+        .iter()
+        .enumerate()
+        .map(|(i, exp)| format!("part {}: {} = {}", i + 1, exp, exp.eval()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Convert the infix format into RPN using Dijkstra's shunting-yard algorithm
+fn rpn(input: &str, part: u8) -> Vec<char> {
+    let mut output: Vec<char> = vec![];
+    let mut stack: Vec<char> = vec![];
+
+    let precedence_rules = if part == 1 {
+        "+*" // equal precedence, left-associative
+    } else {
+        "+" // '+' has highest precedence, left-associative
+    };
+
+    for token in input.chars().filter(|&c| c != ' ') {
+        match token {
+            '0'..='9' => output.push(token),
+            '(' => stack.push(token),
+            ')' => {
+                while let Some(op) = stack.pop() {
+                    if op == '(' {
+                        break;
+                    } else {
+                        output.push(op);
+                    }
+                }
+            }
+            '+' | '*' => {
+                if let Some(op) = stack.pop() {
+                    if precedence_rules.contains(op) {
+                        output.push(op);
+                    } else {
+                        stack.push(op); // put it back
+                    }
+                }
+                stack.push(token);
+            }
+            _ => panic!("Unexpected token {}", token),
+        }
+    }
+
+    while let Some(op) = stack.pop() {
+        output.push(op);
+    }
+
+    output
+}
+
+fn ast(tokens: &[char]) -> Exp {
+    let mut stack: Vec<Exp> = vec![];
+
+    for token in tokens {
+        match token {
+            '0'..='9' => {
+                let n = token.to_digit(10).expect("Invalid number");
+                stack.push(Exp::Number(n as u64))
+            }
+            '+' => {
+                let right = stack.pop().unwrap();
+                let left = stack.pop().unwrap();
+                stack.push(Exp::Add(Box::new(left), Box::new(right)));
+            }
+            '*' => {
+                let right = stack.pop().unwrap();
+                let left = stack.pop().unwrap();
+                stack.push(Exp::Mul(Box::new(left), Box::new(right)));
+            }
+            _ => panic!("Unexpected token {}", token),
+        }
+    }
+
+    stack.pop().unwrap()
+}
+
+/// Check that `line`, a slice of `input`, is a well-formed expression of single digit numbers
+fn check(input: &str, line: &str) -> Result<(), ParseError> {
+    let mut depth = 0;
+    let mut operand = true; // expecting an operand rather than an operator
+
+    for (i, token) in line.char_indices().filter(|&(_, c)| c != ' ') {
+        let ok = match token {
+            '0'..='9' if operand => {
+                operand = false;
+                true
+            }
+            '(' if operand => {
+                depth += 1;
+                true
+            }
+            ')' if !operand && depth > 0 => {
+                depth -= 1;
+                true
+            }
+            '+' | '*' if !operand => {
+                operand = true;
+                true
+            }
+            _ => false,
+        };
+
+        if !ok {
+            let reason = format!("unexpected token {:?}", token);
+            return Err(ParseError::at(input, &line[i..], reason));
+        }
+    }
+
+    if operand || depth > 0 {
+        return Err(ParseError::at(
+            input,
+            &line[line.len()..],
+            "incomplete expression",
+        ));
+    }
+
+    Ok(())
+}
+
+/// The expressions of the homework, one per line
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    input
+        .lines()
+        .map(|line| line.trim())
+        .map(|line| check(input, line).map(|_| line.to_string()))
+        .collect()
+}
+
+pub fn solve_part1(input: &[String]) -> u64 {
+    input.iter().map(|line| Exp::v1(line).eval()).sum()
+}
+
+pub fn solve_part2(input: &[String]) -> u64 {
+    input.iter().map(|line| Exp::v2(line).eval()).sum()
+}
+
+/// An expression of two to four operands, single digits or nested expressions in parentheses
+fn expression(rng: &mut Rng, depth: usize) -> String {
+    let mut text = String::new();
+    for i in 0..rng.between(2, 4) {
+        if i > 0 {
+            text += if rng.chance(0.5) { " + " } else { " * " };
+        }
+        if depth < 2 && rng.chance(0.25) {
+            text += &format!("({})", expression(rng, depth + 1));
+        } else {
+            text += &rng.between(1, 9).to_string();
+        }
+    }
+    text
+}
+
+/// `size` homework expressions, nested in up to two levels of parentheses
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size).map(|_| expression(rng, 0) + "\n").collect()
+}
+
+/// An expression tree of single digits, up to `depth` operators deep
+fn tree(rng: &mut Rng, depth: usize) -> Exp {
+    if depth == 0 || rng.chance(0.3) {
+        return Exp::Number(rng.between(1, 9) as u64);
+    }
+    let a = Box::new(tree(rng, depth - 1));
+    let b = Box::new(tree(rng, depth - 1));
+    if rng.chance(0.5) {
+        Exp::Add(a, b)
+    } else {
+        Exp::Mul(a, b)
+    }
+}
+
+/// The expressions with an operation replaced by one of its operands or a smaller number
+fn simpler(exp: &Exp) -> Vec<Exp> {
+    let (a, b) = match exp {
+        Exp::Number(n) => return (0..*n).map(Exp::Number).collect(),
+        Exp::Add(a, b) | Exp::Mul(a, b) => (a, b),
+    };
+    let op = |a, b| match exp {
+        Exp::Add(..) => Exp::Add(a, b),
+        _ => Exp::Mul(a, b),
+    };
+
+    let mut simpler = vec![(**a).clone(), (**b).clone()];
+    for a in self::simpler(a) {
+        simpler.push(op(Box::new(a), b.clone()));
+    }
+    for b in self::simpler(b) {
+        simpler.push(op(a.clone(), Box::new(b)));
+    }
+    simpler
+}
+
+/// Compare `Exp::v1` and `Exp::v2` on `cases` random expression trees, each written with the
+/// parentheses the precedence of the version needs.  Both must evaluate the same tree.
+pub fn compare_versions(rng: &mut Rng, cases: usize) -> Option<Disagreement<Exp, u64>> {
+    compare(
+        rng,
+        cases,
+        |rng| tree(rng, 4),
+        simpler,
+        |exp| Exp::v1(&exp.format(1)).eval(),
+        |exp| Exp::v2(&exp.format(2)).eval(),
+    )
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(homework: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(homework).into())
+    }
+
+    fn part2(homework: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(homework).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    const QUERIES: &'static [(&'static str, &'static str)] = &[
+        (
+            "eval <expression>",
+            "Evaluate an expression the ways of both parts",
+        ),
+        (
+            "line <n>",
+            "Evaluate the expression on line <n> of the homework",
+        ),
+    ];
+
+    fn query(homework: &Self::Input, query: &str, args: &[&str]) -> Option<Reply> {
+        Some(match query {
+            "eval" => {
+                let exp = args.join(" ");
+                check(&exp, &exp)
+                    .map(|_| evaluate(&exp))
+                    .map_err(|e| e.to_string())
+            }
+            "line" => arg(args, 0, "line").and_then(|n: usize| {
+                let exp = n
+                    .checked_sub(1)
+                    .and_then(|i| homework.get(i))
+                    .ok_or_else(|| format!("no line {}", n))?;
+                Ok(format!("{}\n{}", exp, evaluate(exp)))
+            }),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example1() {
+        let exp = "1 + 2 * 3 + 4 * 5 + 6";
+        assert_eq!(Exp::v1(exp).eval(), 71);
+        assert_eq!(Exp::v2(exp).eval(), 231);
+    }
+
+    #[test]
+    fn test_example2() {
+        let exp = "1 + (2 * 3) + (4 * (5 + 6))";
+        assert_eq!(Exp::v1(exp).eval(), 51);
+        assert_eq!(Exp::v2(exp).eval(), 51);
+    }
+
+    #[test]
+    fn test_example3() {
+        let exp = "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2";
+        assert_eq!(Exp::v1(exp).eval(), 13632);
+        assert_eq!(Exp::v2(exp).eval(), 23340);
+    }
+
+    #[test]
+    fn evaluates_queries() {
+        let homework = parse("2 * 3 + (4 * 5)\n").unwrap();
+        assert_eq!(
+            Day18::query(&homework, "eval", &["1", "+", "2", "*", "3", "+", "4"]),
+            Some(Ok(
+                "part 1: ((1 + 2) * 3) + 4 = 13\npart 2: (1 + 2) * (3 + 4) = 21".to_string()
+            ))
+        );
+        assert_eq!(
+            Day18::query(&homework, "line", &["1"]),
+            Some(Ok(
+                "2 * 3 + (4 * 5)\npart 1: (2 * 3) + (4 * 5) = 26\npart 2: 2 * (3 + (4 * 5)) = 46"
+                    .to_string()
+            ))
+        );
+        assert_eq!(
+            Day18::query(&homework, "eval", &["1", "+"]),
+            Some(Err("line 1, column 4: incomplete expression".to_string()))
+        );
+        assert_eq!(
+            Day18::query(&homework, "line", &["2"]),
+            Some(Err("no line 2".to_string()))
+        );
+    }
+
+    #[test]
+    fn formats_expressions() {
+        let n = |n| Box::new(Exp::Number(n));
+        // (1 + 2) * 3 + 4 * 5
+        let exp = Exp::Add(
+            Box::new(Exp::Mul(Box::new(Exp::Add(n(1), n(2))), n(3))),
+            Box::new(Exp::Mul(n(4), n(5))),
+        );
+        assert_eq!(exp.format(1), "1 + 2 * 3 + (4 * 5)");
+        assert_eq!(exp.format(2), "(1 + 2 * 3) + (4 * 5)");
+        assert_eq!(Exp::v1(&exp.format(1)).eval(), 29);
+        assert_eq!(Exp::v2(&exp.format(2)).eval(), 29);
+
+        let exp = Exp::Mul(n(2), n(3));
+        assert_eq!(
+            simpler(&exp),
+            [
+                Exp::Number(2),
+                Exp::Number(3),
+                Exp::Mul(n(0), n(3)),
+                Exp::Mul(n(1), n(3)),
+                Exp::Mul(n(2), n(0)),
+                Exp::Mul(n(2), n(1)),
+                Exp::Mul(n(2), n(2)),
+            ]
+        );
+        assert_eq!(compare_versions(&mut Rng::new(1), 100), None);
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert_eq!(
+            parse("1 + 2\n(3 * 4"),
+            Err(ParseError::new(2, 7, "incomplete expression"))
+        );
+        assert_eq!(
+            parse("1 + 23"),
+            Err(ParseError::new(1, 6, "unexpected token '3'"))
+        );
+        assert_eq!(
+            parse("1 + (2 -"),
+            Err(ParseError::new(1, 8, "unexpected token '-'"))
+        );
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let homework = parse(&generate(&mut Rng::new(seed), 100)).unwrap();
+            assert_eq!(homework.len(), 100);
+            assert!(solve_part1(&homework) > 0);
+            assert!(solve_part2(&homework) > 0);
+        }
+    }
+}
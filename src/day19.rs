@@ -0,0 +1,502 @@
+//! Day 19: Monster Messages
+
+use crate::error::number;
+use crate::input::paragraphs;
+use crate::random::Rng;
+use crate::repl::{arg, Reply};
+use crate::{Answer, ParseError, Solution};
+use itertools::Itertools;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub enum Rule {
+    SingleChar(char),
+    Seq(Vec<usize>),
+    Or(Vec<usize>, Vec<usize>),
+}
+
+impl Rule {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        if input.contains('"') {
+            let mut chars = input.chars();
+            return match (chars.next(), chars.next(), chars.next(), chars.next()) {
+                (Some('"'), Some(c), Some('"'), None) => Ok(Rule::SingleChar(c)),
+                _ => Err(ParseError::at(input, input, "expected a quoted character")),
+            };
+        }
+
+        let seq = |part: &str| -> Result<Vec<usize>, ParseError> {
+            part.split_whitespace().map(|x| number(input, x)).collect()
+        };
+
+        let parts: Vec<_> = input.split('|').collect();
+
+        match parts[..] {
+            [seq1] => Ok(Rule::Seq(seq(seq1)?)),
+            [seq1, seq2] => Ok(Rule::Or(seq(seq1)?, seq(seq2)?)),
+            _ => Err(ParseError::at(
+                input,
+                parts[2],
+                "more than two alternatives",
+            )),
+        }
+    }
+
+    fn references(&self) -> Vec<usize> {
+        match self {
+            Rule::SingleChar(_) => vec![],
+            Rule::Seq(seq) => seq.clone(),
+            Rule::Or(a, b) => a.iter().chain(b).copied().collect(),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seq = |seq: &[usize]| seq.iter().map(|i| i.to_string()).join(" ");
+        match self {
+            Rule::SingleChar(c) => write!(f, "\"{}\"", c),
+            Rule::Seq(s) => write!(f, "{}", seq(s)),
+            Rule::Or(a, b) => write!(f, "{} | {}", seq(a), seq(b)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Input {
+    pub rules: HashMap<usize, Rule>,
+    pub lines: Vec<String>,
+}
+
+pub fn parse(file: &str) -> Result<Input, ParseError> {
+    let mut rules: HashMap<usize, Rule> = HashMap::new();
+    let mut lines: Vec<String> = vec![];
+
+    let mut parts = paragraphs(file);
+
+    let rule_lines = parts.next().unwrap_or_default();
+    let msg_lines = parts
+        .next()
+        .ok_or_else(|| ParseError::at_end(file, "missing messages"))?;
+
+    for line in rule_lines.lines() {
+        let line = line.trim();
+        let (id, ctx) = line
+            .split_once(':')
+            .ok_or_else(|| ParseError::at(file, line, "expected \"<id>: <rule>\""))?;
+
+        let id: usize = number(file, id)?;
+        let ctx = ctx.trim();
+        let rule = Rule::new(ctx).map_err(|e| e.within(file, ctx))?;
+        if rules.insert(id, rule).is_some() {
+            return Err(ParseError::at(file, line, format!("duplicate rule {}", id)));
+        }
+    }
+
+    for line in rule_lines.lines() {
+        let line = line.trim();
+        let (id, ctx) = line.split_once(':').unwrap();
+        let undefined = rules[&id.parse().unwrap()]
+            .references()
+            .into_iter()
+            .find(|id| !rules.contains_key(id));
+        if let Some(id) = undefined {
+            return Err(ParseError::at(
+                file,
+                ctx.trim(),
+                format!("undefined rule {}", id),
+            ));
+        }
+    }
+
+    if !rules.contains_key(&0) {
+        return Err(ParseError::at(file, rule_lines, "missing rule 0"));
+    }
+
+    for line in msg_lines.lines() {
+        let line = line.trim();
+        lines.push(line.to_string());
+    }
+
+    Ok(Input { rules, lines })
+}
+
+impl Input {
+    /// The regular expression matching rule `i`, expanded at most `limit` levels deep
+    pub fn to_re(&self, i: usize, limit: usize) -> String {
+        if limit == 0 {
+            return "".to_string();
+        }
+
+        match &self.rules[&i] {
+            Rule::SingleChar(c) => c.to_string(),
+            Rule::Seq(seq) => {
+                let mut res = "".to_string();
+                for i in seq {
+                    res += &self.to_re(*i, limit - 1);
+                }
+                res
+            }
+
+            Rule::Or(a, b) => {
+                let mut res_a = "".to_string();
+                for i in a {
+                    res_a += &self.to_re(*i, limit - 1);
+                }
+
+                let mut res_b = "".to_string();
+                for i in b {
+                    res_b += &self.to_re(*i, limit - 1);
+                }
+
+                "(".to_owned() + &res_a + "|" + &res_b + ")"
+            }
+        }
+    }
+}
+
+/// The regular expression matching rule 0 in the given part, where rules 8 and 11 loop
+fn matcher(input: &Input, part: u8) -> Regex {
+    if part == 1 {
+        return Regex::new(&("^".to_owned() + &input.to_re(0, 1000) + "$")).unwrap();
+    }
+
+    let mut input = input.to_owned();
+
+    input.rules.insert(8, Rule::Or(vec![42], vec![42, 8]));
+    input
+        .rules
+        .insert(11, Rule::Or(vec![42, 31], vec![42, 11, 31]));
+
+    Regex::new(&("^".to_owned() + &input.to_re(0, 20) + "$")).unwrap()
+}
+
+pub fn solve_part1(input: &Input) -> usize {
+    let re = matcher(input, 1);
+
+    input.lines.iter().filter(|x| re.is_match(x)).count()
+}
+
+pub fn solve_part2(input: &Input) -> usize {
+    let re = matcher(input, 2);
+
+    input.lines.iter().filter(|x| re.is_match(x)).count()
+}
+
+/// Random rules over the letters `a` and `b`
+struct Grammar {
+    rules: HashMap<usize, Rule>,
+    /// The rule numbers not taken yet
+    free: Vec<usize>,
+    /// The rules of every level, matching words of `2^level` letters
+    levels: Vec<Vec<usize>>,
+}
+
+impl Grammar {
+    fn new(rng: &mut Rng, levels: usize, reserved: &[usize]) -> Self {
+        let mut free: Vec<usize> = (0..200).filter(|id| !reserved.contains(id)).collect();
+        rng.shuffle(&mut free);
+
+        let mut rules = HashMap::new();
+        let (a, b) = (free.pop().unwrap(), free.pop().unwrap());
+        rules.insert(a, Rule::SingleChar('a'));
+        rules.insert(b, Rule::SingleChar('b'));
+
+        let mut levels = vec![vec![]; levels + 1];
+        levels[0] = vec![a, b];
+        Self {
+            rules,
+            free,
+            levels,
+        }
+    }
+
+    /// A rule of `level`, sometimes one defined before
+    fn rule(&mut self, rng: &mut Rng, level: usize) -> usize {
+        if level == 0 || (!self.levels[level].is_empty() && rng.chance(0.3)) {
+            return *rng.choose(&self.levels[level]);
+        }
+        let id = self.free.pop().expect("out of rule numbers");
+        self.define(rng, id, level);
+        id
+    }
+
+    /// Define rule `id` of `level` as a sequence of two rules of the level below, or a choice
+    /// between two such sequences
+    fn define(&mut self, rng: &mut Rng, id: usize, level: usize) {
+        let first = self.pair(rng, level - 1);
+        let rule = if rng.chance(0.7) {
+            Rule::Or(first, self.pair(rng, level - 1))
+        } else {
+            Rule::Seq(first)
+        };
+        self.rules.insert(id, rule);
+        self.levels[level].push(id);
+    }
+
+    fn pair(&mut self, rng: &mut Rng, level: usize) -> Vec<usize> {
+        vec![self.rule(rng, level), self.rule(rng, level)]
+    }
+
+    /// A word matched by rule `id`
+    fn sample(&self, rng: &mut Rng, id: usize) -> String {
+        let seq = match &self.rules[&id] {
+            Rule::SingleChar(c) => return c.to_string(),
+            Rule::Seq(seq) => seq,
+            Rule::Or(a, b) => rng.choose(&[a, b]),
+        };
+        seq.iter().map(|&i| self.sample(rng, i)).collect()
+    }
+}
+
+/// Rules shaped like the puzzle's, `0: 8 11`, `8: 42` and `11: 42 31` where 42 and 31 match
+/// words of eight letters, and `size` messages, at least one.  Some messages match rule 0, some
+/// only with the loops of part 2 and most not at all.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut grammar = Grammar::new(rng, 3, &[0, 8, 11, 31, 42]);
+    grammar.define(rng, 42, 3);
+    grammar.define(rng, 31, 3);
+    grammar.rules.insert(0, Rule::Seq(vec![8, 11]));
+    grammar.rules.insert(8, Rule::Seq(vec![42]));
+    grammar.rules.insert(11, Rule::Seq(vec![42, 31]));
+
+    let mut rules: Vec<String> = grammar
+        .rules
+        .iter()
+        .sorted_by_key(|&(id, _)| id)
+        .map(|(id, rule)| {
+            let seq = |seq: &[usize]| seq.iter().join(" ");
+            match rule {
+                Rule::SingleChar(c) => format!("{}: \"{}\"\n", id, c),
+                Rule::Seq(s) => format!("{}: {}\n", id, seq(s)),
+                Rule::Or(a, b) => format!("{}: {} | {}\n", id, seq(a), seq(b)),
+            }
+        })
+        .collect();
+    rng.shuffle(&mut rules);
+
+    let messages: String = (0..size.max(1))
+        .map(|_| {
+            let (m, n) = match rng.below(4) {
+                0 => (2, 1),
+                1 => {
+                    let m = rng.between(2, 5);
+                    (m, rng.between(1, m - 1))
+                }
+                _ => {
+                    let len = 8 * rng.between(2, 6) as usize;
+                    return rng.word("ab", len) + "\n";
+                }
+            };
+            let words: String = (0..m)
+                .map(|_| 42)
+                .chain((0..n).map(|_| 31))
+                .map(|id| grammar.sample(rng, id))
+                .collect();
+            words + "\n"
+        })
+        .collect();
+
+    format!("{}\n{}", rules.concat(), messages)
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(input).into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(input).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    const QUERIES: &'static [(&'static str, &'static str)] = &[
+        ("rule <n>", "The rule with the given number"),
+        ("regex <n>", "The regular expression of a rule in part 1"),
+        (
+            "match <message>",
+            "Whether rule 0 matches a message in each part",
+        ),
+    ];
+
+    fn query(input: &Self::Input, query: &str, args: &[&str]) -> Option<Reply> {
+        let rule = || {
+            let i = arg(args, 0, "rule")?;
+            match input.rules.get(&i) {
+                Some(rule) => Ok((i, rule)),
+                None => Err(format!("no rule {}", i)),
+            }
+        };
+
+        Some(match query {
+            "rule" => rule().map(|(i, rule)| format!("{}: {}", i, rule)),
+            "regex" => rule().map(|(i, _)| input.to_re(i, 1000)),
+            "match" => arg(args, 0, "message").map(|message: String| {
+                (1..=2)
+                    .map(|part| {
+                        let matches = matcher(input, part).is_match(&message);
+                        format!(
+                            "part {}: {}",
+                            part,
+                            if matches { "match" } else { "no match" }
+                        )
+                    })
+                    .join("\n")
+            }),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const EXAMPLE: &str = "\
+        0: 4 1 5
+        1: 2 3 | 3 2
+        2: 4 4 | 5 5
+        3: 4 5 | 5 4
+        4: \"a\"
+        5: \"b\"
+
+        ababbb
+        bababa
+        abbbab
+        aaabbb
+        aaaabbb\
+        ";
+
+    const EXAMPLE2: &str = "\
+        42: 9 14 | 10 1
+        9: 14 27 | 1 26
+        10: 23 14 | 28 1
+        1: \"a\"
+        11: 42 31
+        5: 1 14 | 15 1
+        19: 14 1 | 14 14
+        12: 24 14 | 19 1
+        16: 15 1 | 14 14
+        31: 14 17 | 1 13
+        6: 14 14 | 1 14
+        2: 1 24 | 14 4
+        0: 8 11
+        13: 14 3 | 1 12
+        15: 1 | 14
+        17: 14 2 | 1 7
+        23: 25 1 | 22 14
+        28: 16 1
+        4: 1 1
+        20: 14 14 | 1 15
+        3: 5 14 | 16 1
+        27: 1 6 | 14 18
+        14: \"b\"
+        21: 14 1 | 1 14
+        25: 1 1 | 1 14
+        22: 14 14
+        8: 42
+        26: 14 22 | 1 20
+        18: 15 15
+        7: 14 5 | 1 21
+        24: 14 1
+
+        abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
+        bbabbbbaabaabba
+        babbbbaabbbbbabbbbbbaabaaabaaa
+        aaabbbbbbaaaabaababaabababbabaaabbababababaaa
+        bbbbbbbaaaabbbbaaabbabaaa
+        bbbababbbbaaaaaaaabbababaaababaabab
+        ababaaaaaabaaab
+        ababaaaaabbbaba
+        baabbaaaabbaaaababbaababb
+        abbbbabbbbaaaababbbbbbaaaababb
+        aaaaabbaabaaaaababaa
+        aaaabbaaaabbaaa
+        aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
+        babaaabbbaaabaababbaabababaaab
+        aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba\
+        ";
+
+    #[test]
+    fn parses_puzzle() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(input.rules.len(), 6);
+        assert_eq!(input.lines.len(), 5);
+        assert_eq!(
+            parse(&EXAMPLE.replace('\n', "\r\n")).unwrap().lines,
+            input.lines
+        );
+
+        assert_eq!(
+            parse("0: 1 2\n1: \"a\"\n\nab").unwrap_err(),
+            ParseError::new(1, 4, "undefined rule 2")
+        );
+        assert_eq!(
+            parse("0: 1 | 1 1 | 1\n1: \"a\"\n\nab").unwrap_err(),
+            ParseError::new(1, 13, "more than two alternatives")
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&input), 2);
+    }
+
+    #[test]
+    fn solves_part2() {
+        let input = parse(EXAMPLE2).unwrap();
+        assert_eq!(solve_part2(&input), 12);
+    }
+
+    #[test]
+    fn answers_queries() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            Day19::query(&input, "rule", &["1"]),
+            Some(Ok("1: 2 3 | 3 2".to_string()))
+        );
+        assert_eq!(
+            Day19::query(&input, "rule", &["4"]),
+            Some(Ok("4: \"a\"".to_string()))
+        );
+        assert_eq!(
+            Day19::query(&input, "regex", &["3"]),
+            Some(Ok("(ab|ba)".to_string()))
+        );
+        assert_eq!(
+            Day19::query(&input, "regex", &["6"]),
+            Some(Err("no rule 6".to_string()))
+        );
+
+        let input = parse(EXAMPLE2).unwrap();
+        assert_eq!(
+            Day19::query(&input, "match", &["babbbbaabbbbbabbbbbbaabaaabaaa"]),
+            Some(Ok("part 1: no match\npart 2: match".to_string()))
+        );
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..5 {
+            let input = parse(&generate(&mut Rng::new(seed), 50)).unwrap();
+            assert_eq!(input.lines.len(), 50);
+            let (part1, part2) = (solve_part1(&input), solve_part2(&input));
+            assert!(0 < part1 && part1 <= part2);
+        }
+        let input = parse(&generate(&mut Rng::new(0), 0)).unwrap();
+        assert_eq!(input.lines.len(), 1);
+    }
+}
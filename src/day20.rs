@@ -0,0 +1,784 @@
+//! Day 20: Jurassic Jigsaw
+
+use crate::error::number;
+use crate::grid::Grid;
+use crate::input::paragraphs;
+use crate::random::Rng;
+use crate::repl::{arg, Reply};
+use crate::{Answer, ParseError, Solution};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tile {
+    pub id: usize,
+    pub pixels: Grid<char>,
+}
+
+impl Tile {
+    pub fn from_input(input: &str) -> Result<Self, ParseError> {
+        let header = input.lines().next().unwrap_or_default().trim();
+        let id = header
+            .strip_prefix("Tile ")
+            .and_then(|id| id.strip_suffix(':'))
+            .ok_or_else(|| ParseError::at(input, header, "expected \"Tile <id>:\""))?;
+        let id = number(input, id)?;
+
+        let body = input.split_once('\n').map_or("", |(_, body)| body);
+        let size = body.lines().count();
+        if let Some(row) = body.lines().find(|row| row.trim().len() != size) {
+            return Err(ParseError::at(input, row.trim(), "tile is not square"));
+        }
+        let pixels = Grid::parse(body, ".#").map_err(|e| e.within(input, body))?;
+
+        Ok(Self { id, pixels })
+    }
+
+    pub fn size(&self) -> usize {
+        self.pixels.width()
+    }
+
+    /// The top, bottom, left and right borders
+    pub fn borders(&self) -> Vec<String> {
+        let last = self.size() - 1;
+
+        vec![
+            self.pixels.row(0).iter().collect(),
+            self.pixels.row(last).iter().collect(),
+            self.pixels.column(0).collect(),
+            self.pixels.column(last).collect(),
+        ]
+    }
+
+    pub fn top(&self) -> String {
+        self.borders()[0].clone()
+    }
+
+    pub fn bottom(&self) -> String {
+        self.borders()[1].clone()
+    }
+
+    pub fn left(&self) -> String {
+        self.borders()[2].clone()
+    }
+
+    pub fn right(&self) -> String {
+        self.borders()[3].clone()
+    }
+
+    /// Rotate the tile 90 degrees counter-clockwise
+    pub fn rot90(&self) -> Self {
+        Self {
+            id: self.id,
+            pixels: self.pixels.rotate_left(),
+        }
+    }
+
+    pub fn flip(&self) -> Self {
+        Self {
+            id: self.id,
+            pixels: self.pixels.flip(),
+        }
+    }
+
+    pub fn possible_borders(&self) -> Vec<String> {
+        let mut res = vec![];
+        res.extend_from_slice(&self.borders());
+        res.extend_from_slice(
+            &self
+                .borders()
+                .iter()
+                .map(|x| x.chars().rev().collect())
+                .collect::<Vec<String>>(),
+        );
+
+        res
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Tile>, ParseError> {
+    let tiles = paragraphs(input)
+        .map(|section| Tile::from_input(section).map_err(|e| e.within(input, section)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if tiles.is_empty() {
+        return Err(ParseError::at_end(input, "no tiles"));
+    }
+    for (tile, section) in tiles.iter().zip(paragraphs(input)) {
+        if tile.size() != tiles[0].size() {
+            let reason = format!(
+                "tile is {} pixels wide, expected {}",
+                tile.size(),
+                tiles[0].size()
+            );
+            return Err(ParseError::at(input, section, reason));
+        }
+    }
+    // the tiles make a square image of at least two by two tiles
+    let side = (1..).find(|n| n * n >= tiles.len()).unwrap();
+    if side < 2 || side * side != tiles.len() {
+        let reason = format!(
+            "expected a square number of tiles from 4, found {}",
+            tiles.len()
+        );
+        return Err(ParseError::at_end(input, reason));
+    }
+
+    Ok(tiles)
+}
+
+/// The IDs of the tiles sharing a border with each tile
+pub fn neigbours(tiles: &[Tile]) -> HashMap<usize, Vec<usize>> {
+    let mut neigbours = HashMap::new();
+    for i in 0..tiles.len() {
+        for j in 0..i {
+            if tiles[i]
+                .possible_borders()
+                .iter()
+                .any(|border| tiles[j].borders().contains(border))
+            {
+                let i_id = tiles[i].id;
+                let j_id = tiles[j].id;
+                neigbours.entry(i_id).or_insert_with(Vec::new).push(j_id);
+                neigbours.entry(j_id).or_insert_with(Vec::new).push(i_id);
+            }
+        }
+    }
+    neigbours
+}
+
+pub fn find_corners(tiles: &[Tile]) -> Vec<usize> {
+    neigbours(tiles)
+        .iter()
+        .filter_map(|(k, v)| if v.len() == 2 { Some(*k) } else { None })
+        .collect()
+}
+
+/// The IDs of the four corner tiles, the only ones with two neighbours
+fn corners(tiles: &[Tile]) -> Result<Vec<usize>, String> {
+    let corners = find_corners(tiles);
+    if corners.len() != 4 {
+        return Err(format!("{} corner tiles, expected 4", corners.len()));
+    }
+    Ok(corners)
+}
+
+/// Assemble the tiles into one image without their borders, or tell why they don't fit
+pub fn reconstruct_image(tiles: &[Tile]) -> Result<Tile, String> {
+    let n = neigbours(tiles);
+    let corners = corners(tiles)?;
+    let count = tiles.len();
+
+    let tiles: HashMap<usize, Tile> = tiles.iter().map(|t| (t.id, t.clone())).collect();
+    let adjacent: HashMap<usize, Vec<Tile>> = n
+        .iter()
+        .map(|(k, v)| (*k, v.iter().map(|id| tiles[id].clone()).collect()))
+        .collect();
+    let doesnt_fit = || "the tiles don't fit together".to_string();
+
+    let mut reconstruct: Vec<Tile> = vec![];
+
+    // fix this as top left corner
+    let mut current = tiles[&corners[0]].clone();
+    current = make_top_left(&current, &adjacent[&current.id]).ok_or_else(|| {
+        format!(
+            "no orientation of corner tile {} fits its neighbours",
+            current.id
+        )
+    })?;
+    let mut top_left = current.clone();
+    let mut n_rows = 0;
+
+    loop {
+        while let Some(x) = right_neighbour(&current, &adjacent[&current.id]) {
+            reconstruct.push(current.clone());
+            current = x.clone();
+            if reconstruct.len() >= count {
+                return Err(doesnt_fit());
+            }
+        }
+        reconstruct.push(current.clone());
+
+        if n_rows == 0 {
+            n_rows = reconstruct.len();
+        }
+        if !reconstruct.len().is_multiple_of(n_rows) || reconstruct.len() > count {
+            return Err(doesnt_fit());
+        }
+
+        if let Some(next) = bottom_neighbour(&top_left, &adjacent[&top_left.id]) {
+            top_left = next;
+            current = top_left.clone();
+        } else {
+            break;
+        }
+    }
+    if reconstruct.len() != count || n_rows * n_rows != count {
+        return Err(doesnt_fit());
+    }
+
+    // assemble the image from the tiles without borders
+    let inner = reconstruct[0].size() - 2;
+    let size = n_rows * inner;
+    let image = Grid::from_fn(size, size, |col, row| {
+        let tile = &reconstruct[col / inner + row / inner * n_rows];
+        tile.pixels[(col % inner + 1, row % inner + 1)]
+    });
+
+    Ok(Tile {
+        id: 0,
+        pixels: image,
+    })
+}
+
+pub fn solve_part1(tiles: &[Tile]) -> Result<usize, String> {
+    Ok(corners(tiles)?.iter().product())
+}
+
+pub fn solve_part2(tiles: &[Tile]) -> Result<usize, String> {
+    let image = reconstruct_image(tiles)?;
+
+    let mut monster_count = 0;
+    for v in variants(&image) {
+        for i in 0..image.size().saturating_sub(3) {
+            for j in 0..image.size().saturating_sub(20) {
+                if match_monster(&v, i, j) {
+                    monster_count += 1;
+                }
+            }
+        }
+
+        if monster_count > 0 {
+            break;
+        }
+    }
+
+    Ok(image.pixels.iter().filter(|&&c| c == '#').count() - 15 * monster_count)
+}
+
+/// The sea monster
+const MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
+
+#[allow(clippy::needless_range_loop)]
+fn match_monster(image: &Tile, i: usize, j: usize) -> bool {
+    let monster: Vec<Vec<char>> = MONSTER.iter().map(|row| row.chars().collect()).collect();
+
+    for mi in 0..3 {
+        for mj in 0..20 {
+            if monster[mi][mj] != '#' {
+                continue;
+            }
+
+            if image.pixels[(j + mj, i + mi)] != '#' {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// All the rotated and flipped versions of the tile
+pub fn variants(tile: &Tile) -> Vec<Tile> {
+    vec![
+        tile.clone(),
+        tile.rot90(),
+        tile.rot90().rot90(),
+        tile.rot90().rot90().rot90(),
+        tile.flip(),
+        tile.rot90().flip(),
+        tile.rot90().rot90().flip(),
+        tile.rot90().rot90().rot90().flip(),
+    ]
+}
+
+fn right_neighbour(current: &Tile, candidates: &[Tile]) -> Option<Tile> {
+    for c in candidates {
+        for v in variants(c) {
+            if current.right() == v.left() {
+                return Some(v);
+            }
+        }
+    }
+
+    None
+}
+
+fn bottom_neighbour(current: &Tile, candidates: &[Tile]) -> Option<Tile> {
+    for c in candidates {
+        for v in variants(c) {
+            if current.bottom() == v.top() {
+                return Some(v);
+            }
+        }
+    }
+
+    None
+}
+
+fn make_top_left(current: &Tile, candidates: &[Tile]) -> Option<Tile> {
+    variants(current).into_iter().find(|v| {
+        candidates[0].possible_borders().contains(&v.right())
+            && candidates[1].possible_borders().contains(&v.bottom())
+    })
+}
+
+/// The tiles of a random image of `size` by `size` tiles, between 3 and 12, in place.  One to
+/// three sea monsters hide in the image, and every border matches the one border next to it
+/// and no other, whichever way round.
+pub fn jigsaw(rng: &mut Rng, size: usize) -> Vec<Vec<Tile>> {
+    let n = size.clamp(3, 12);
+    // neighbouring tiles share their borders
+    let side = 9 * n + 1;
+    let mut pixels: Vec<Vec<char>> = (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| if rng.chance(0.4) { '#' } else { '.' })
+                .collect()
+        })
+        .collect();
+
+    // the monsters go in the image without the borders, 8 pixels per tile
+    let inner = |x: usize| x / 8 * 9 + x % 8 + 1;
+    let mut monsters: Vec<(usize, usize)> = vec![];
+    for _ in 0..rng.between(1, 3) {
+        let (row, col) = (rng.below(8 * n - 2), rng.below(8 * n - 19));
+        if monsters
+            .iter()
+            .all(|&(r, c)| r.abs_diff(row) >= 3 || c.abs_diff(col) >= 20)
+        {
+            monsters.push((row, col));
+        }
+    }
+    for &(row, col) in &monsters {
+        for (i, line) in MONSTER.iter().enumerate() {
+            for (j, _) in line.match_indices('#') {
+                pixels[inner(row + i)][inner(col + j)] = '#';
+            }
+        }
+    }
+
+    // half the corners dark: there are fewer ways to draw a border with both ends alike
+    for r in 0..=n {
+        for c in 0..=n {
+            pixels[9 * r][9 * c] = if rng.chance(0.5) { '#' } else { '.' };
+        }
+    }
+
+    // redraw the borders until no border reads the same as another or as itself reversed
+    let borders: Vec<Vec<(usize, usize)>> = (0..=n)
+        .flat_map(|line| (0..n).map(move |k| (9 * line, 9 * k)))
+        .flat_map(|(line, k)| {
+            let horizontal = (0..10).map(|x| (line, k + x)).collect();
+            let vertical = (0..10).map(|x| (k + x, line)).collect();
+            vec![horizontal, vertical]
+        })
+        .collect();
+    loop {
+        let mut seen = HashSet::new();
+        let mut redrawn = false;
+        for border in &borders {
+            let forward: String = border.iter().map(|&(r, c)| pixels[r][c]).collect();
+            let backward: String = forward.chars().rev().collect();
+            if forward == backward || !seen.insert(forward.min(backward)) {
+                // the corners belong to other borders as well, so they change only now and
+                // then, when there are too many borders of their kind
+                let redraw = if rng.chance(0.1) {
+                    &border[..]
+                } else {
+                    &border[1..9]
+                };
+                for &(r, c) in redraw {
+                    pixels[r][c] = if rng.chance(0.5) { '#' } else { '.' };
+                }
+                redrawn = true;
+            }
+        }
+        if !redrawn {
+            break;
+        }
+    }
+
+    let ids = rng.sample(&(1000..10000).collect::<Vec<usize>>(), n * n);
+    (0..n)
+        .map(|r| {
+            (0..n)
+                .map(|c| Tile {
+                    id: ids[r * n + c],
+                    pixels: Grid::from_fn(10, 10, |col, row| pixels[9 * r + row][9 * c + col]),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The tiles of a random image of `size` by `size` tiles, see `jigsaw`, every tile rotated or
+/// flipped and in a random order
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut tiles: Vec<Tile> = jigsaw(rng, size)
+        .into_iter()
+        .flatten()
+        .map(|tile| variants(&tile).swap_remove(rng.below(8)))
+        .collect();
+    rng.shuffle(&mut tiles);
+
+    tiles
+        .iter()
+        .map(|tile| format!("Tile {}:\n{}", tile.id, tile.pixels))
+        .join("\n")
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Vec<Tile>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(tiles: &Self::Input) -> Result<Answer, String> {
+        solve_part1(tiles).map(Answer::from)
+    }
+
+    fn part2(tiles: &Self::Input) -> Result<Answer, String> {
+        solve_part2(tiles).map(Answer::from)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    const QUERIES: &'static [(&'static str, &'static str)] = &[
+        ("tile <id>", "The pixels of a tile"),
+        ("neighbours <id>", "The tiles sharing a border with a tile"),
+        ("corners", "The tiles with only two neighbours"),
+    ];
+
+    fn query(tiles: &Self::Input, query: &str, args: &[&str]) -> Option<Reply> {
+        let ids = |mut ids: Vec<usize>| {
+            ids.sort_unstable();
+            ids.iter().map(|id| id.to_string()).join(", ")
+        };
+        let tile = || {
+            let id = arg(args, 0, "tile")?;
+            tiles
+                .iter()
+                .find(|tile| tile.id == id)
+                .ok_or_else(|| format!("no tile {}", id))
+        };
+
+        Some(match query {
+            "tile" => tile().map(|tile| format!("Tile {}:\n{}", tile.id, tile.pixels)),
+            "neighbours" => {
+                tile().map(|tile| ids(neigbours(tiles).remove(&tile.id).unwrap_or_default()))
+            }
+            "corners" => Ok(ids(find_corners(tiles))),
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tile() {
+        let tile = Tile::from_input(TILE).unwrap();
+        assert_eq!(tile.id, 2311);
+        assert_eq!(tile.size(), 10);
+        assert_eq!(
+            tile.pixels.to_string(),
+            "\
+..##.#..#.
+##..#.....
+#...##..#.
+####.#...#
+##.##.###.
+##...#.###
+.#.#.#..##
+..#....#..
+###...#.#.
+..###..###
+"
+        );
+
+        assert_eq!(
+            Tile::from_input("Tile 7:\n#.\n.#.").unwrap_err(),
+            ParseError::new(3, 1, "tile is not square")
+        );
+        assert_eq!(
+            parse("Tile 1:\n#\n\nTile x:\n.").unwrap_err(),
+            ParseError::new(4, 6, "invalid number \"x\"")
+        );
+        assert_eq!(parse("").unwrap_err(), ParseError::new(1, 1, "no tiles"));
+        assert_eq!(
+            parse("Tile 1:\n#.\n.#\n").unwrap_err(),
+            ParseError::new(4, 1, "expected a square number of tiles from 4, found 1")
+        );
+        assert_eq!(
+            parse("Tile 1:\n#.\n.#\n\nTile 2:\n#..\n...\n..#\n").unwrap_err(),
+            ParseError::new(5, 1, "tile is 3 pixels wide, expected 2")
+        );
+    }
+
+    #[test]
+    fn reports_tiles_not_fitting() {
+        // every tile shares its bottom border with all the others
+        let tiles = parse(
+            "\
+Tile 1:\n#..\n...\n...\n
+Tile 2:\n.#.\n...\n...\n
+Tile 3:\n##.\n...\n...\n
+Tile 4:\n###\n...\n...\n",
+        )
+        .unwrap();
+        assert_eq!(
+            solve_part1(&tiles),
+            Err("0 corner tiles, expected 4".to_string())
+        );
+        assert_eq!(
+            Day20::solve(&tiles, 2),
+            Some(Err("0 corner tiles, expected 4".to_string()))
+        );
+    }
+
+    #[test]
+    fn borders() {
+        assert_eq!(
+            Tile::from_input(TILE).unwrap().borders(),
+            vec![
+                "..##.#..#.", // top
+                "..###..###", // bot
+                ".#####..#.", // left
+                "...#.##..#", // right
+            ]
+        );
+    }
+
+    #[test]
+    fn rotate() {
+        let tile = Tile::from_input(TILE).unwrap();
+        assert_eq!(
+            tile.rot90().borders(),
+            vec![
+                "...#.##..#", // top (was right)
+                ".#####..#.", // bot (was left)
+                ".#..#.##..", // left (was top, reversed)
+                "###..###..", // right, (was bot, reversed)
+            ]
+        );
+
+        assert_eq!(tile, tile.rot90().rot90().rot90().rot90());
+    }
+
+    #[test]
+    fn flip_horizontal() {
+        let tile = Tile::from_input(TILE).unwrap();
+
+        assert_eq!(
+            tile.flip().borders(),
+            vec![
+                ".#..#.##..", // top (reversed)
+                "###..###..", // bot (reversed)
+                "...#.##..#", // left (was right)
+                ".#####..#.", // right (was left)
+            ]
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        let tiles = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&tiles), Ok(1951 * 3079 * 2971 * 1171));
+
+        let tiles = parse(&EXAMPLE.replace('\n', "\r\n")).unwrap();
+        assert_eq!(solve_part1(&tiles), Ok(1951 * 3079 * 2971 * 1171));
+    }
+
+    #[test]
+    fn solves_part2() {
+        let tiles = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part2(&tiles), Ok(273));
+    }
+
+    #[test]
+    fn answers_queries() {
+        let tiles = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            Day20::query(&tiles, "neighbours", &["2311"]),
+            Some(Ok("1427, 1951, 3079".to_string()))
+        );
+        assert_eq!(
+            Day20::query(&tiles, "corners", &[]),
+            Some(Ok("1171, 1951, 2971, 3079".to_string()))
+        );
+        assert!(Day20::query(&tiles, "tile", &["2311"])
+            .unwrap()
+            .unwrap()
+            .starts_with("Tile 2311:\n..##.#..#.\n##..#.....\n"));
+        assert_eq!(
+            Day20::query(&tiles, "tile", &["42"]),
+            Some(Err("no tile 42".to_string()))
+        );
+    }
+
+    const TILE: &str = "\
+        Tile 2311:
+        ..##.#..#.
+        ##..#.....
+        #...##..#.
+        ####.#...#
+        ##.##.###.
+        ##...#.###
+        .#.#.#..##
+        ..#....#..
+        ###...#.#.
+        ..###..###\
+    ";
+
+    const EXAMPLE: &str = "\
+        Tile 2311:
+        ..##.#..#.
+        ##..#.....
+        #...##..#.
+        ####.#...#
+        ##.##.###.
+        ##...#.###
+        .#.#.#..##
+        ..#....#..
+        ###...#.#.
+        ..###..###
+
+        Tile 1951:
+        #.##...##.
+        #.####...#
+        .....#..##
+        #...######
+        .##.#....#
+        .###.#####
+        ###.##.##.
+        .###....#.
+        ..#.#..#.#
+        #...##.#..
+
+        Tile 1171:
+        ####...##.
+        #..##.#..#
+        ##.#..#.#.
+        .###.####.
+        ..###.####
+        .##....##.
+        .#...####.
+        #.##.####.
+        ####..#...
+        .....##...
+
+        Tile 1427:
+        ###.##.#..
+        .#..#.##..
+        .#.##.#..#
+        #.#.#.##.#
+        ....#...##
+        ...##..##.
+        ...#.#####
+        .#.####.#.
+        ..#..###.#
+        ..##.#..#.
+
+        Tile 1489:
+        ##.#.#....
+        ..##...#..
+        .##..##...
+        ..#...#...
+        #####...#.
+        #..#.#.#.#
+        ...#.#.#..
+        ##.#...##.
+        ..##.##.##
+        ###.##.#..
+
+        Tile 2473:
+        #....####.
+        #..#.##...
+        #.##..#...
+        ######.#.#
+        .#...#.#.#
+        .#########
+        .###.#..#.
+        ########.#
+        ##...##.#.
+        ..###.#.#.
+
+        Tile 2971:
+        ..#.#....#
+        #...###...
+        #.#.###...
+        ##.##..#..
+        .#####..##
+        .#..####.#
+        #..#.#..#.
+        ..####.###
+        ..#.#.###.
+        ...#.#.#.#
+
+        Tile 2729:
+        ...#.#.#.#
+        ####.#....
+        ..#.#.....
+        ....#..#.#
+        .##..##.#.
+        .#.####...
+        ####.#.#..
+        ##.####...
+        ##..#.##..
+        #.##...##.
+
+        Tile 3079:
+        #.#.#####.
+        .#..######
+        ..#.......
+        ######....
+        ####.#..#.
+        .#...#.##.
+        #.#####.##
+        ..#.###...
+        ..#.......
+        ..#.###...\
+    ";
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..3 {
+            let image = jigsaw(&mut Rng::new(seed), 4);
+            let corners = [
+                image[0][0].id,
+                image[0][3].id,
+                image[3][0].id,
+                image[3][3].id,
+            ];
+            let inner: usize = image
+                .iter()
+                .flatten()
+                .map(|tile| {
+                    tile.pixels
+                        .crop(1, 1, 8, 8)
+                        .iter()
+                        .filter(|&&c| c == '#')
+                        .count()
+                })
+                .sum();
+
+            let tiles = parse(&generate(&mut Rng::new(seed), 4)).unwrap();
+            assert_eq!(tiles.len(), 16);
+            assert_eq!(solve_part1(&tiles), Ok(corners.iter().product()));
+            // at least one monster found
+            assert!(solve_part2(&tiles).unwrap() <= inner - 15);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Day 21: Allergen Assessment
+
+use crate::error::parse_lines;
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use itertools::Itertools;
+use std::collections::BTreeSet;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Food {
+    pub ingredients: HashSet<String>,
+    pub allergens: Vec<String>,
+}
+
+impl Food {
+    pub fn new(line: &str) -> Result<Self, ParseError> {
+        let (ingredients, allergens) = line.trim().split_once(" (contains ").ok_or_else(|| {
+            ParseError::at(line, line.trim_start(), "expected \"(contains ...)\"")
+        })?;
+        let ingredients = ingredients
+            .split(' ')
+            .map(|i| i.trim().to_string())
+            .collect();
+        let allergens = allergens
+            .strip_suffix(')')
+            .ok_or_else(|| ParseError::at_end(line.trim_end(), "expected ')'"))?
+            .split(',')
+            .map(|i| i.trim().to_string())
+            .collect();
+
+        Ok(Self {
+            ingredients,
+            allergens,
+        })
+    }
+}
+
+pub fn parse(file: &str) -> Result<Vec<Food>, ParseError> {
+    parse_lines(file, Food::new)
+}
+
+/// The number of appearances of the inert ingredients and the canonical
+/// dangerous ingredient list, or why an allergen is in no ingredient or in several
+pub fn solve(food: &[Food]) -> (usize, Result<String, String>) {
+    let mut ingredients_per_allergen: HashMap<String, HashSet<String>> = HashMap::new();
+
+    for f in food {
+        for a in &f.allergens {
+            let mut x: HashSet<String> = ingredients_per_allergen
+                .get(a)
+                .unwrap_or(&f.ingredients)
+                .to_owned();
+
+            x = x.intersection(&f.ingredients).cloned().collect();
+
+            ingredients_per_allergen.insert(a.to_string(), x);
+        }
+    }
+
+    let inert_ingredients: HashSet<String> = ingredients_per_allergen
+        .values()
+        .flat_map(|x| x.iter().map(|v| v.to_string()))
+        .collect();
+
+    let num_inert = food
+        .iter()
+        .map(|f| {
+            let food_ingredients: HashSet<String> = f.ingredients.iter().cloned().collect();
+            food_ingredients.difference(&inert_ingredients).count()
+        })
+        .sum();
+
+    let mut out: Vec<(String, String)> = vec![];
+    let keys: Vec<String> = ingredients_per_allergen.keys().cloned().collect();
+
+    while out.len() < keys.len() {
+        let resolved = out.len();
+        for i in &keys {
+            if ingredients_per_allergen[i].len() == 1 && out.iter().all(|(a, _)| a != i) {
+                for v in &ingredients_per_allergen[i] {
+                    out.push((i.to_string(), v.to_string())); // allergen, ingredient
+                }
+
+                for j in &keys {
+                    if i != j {
+                        ingredients_per_allergen
+                            .get_mut(j)
+                            .unwrap()
+                            .remove(&out.iter().last().unwrap().1);
+                    }
+                }
+            }
+        }
+
+        // no allergen found in this pass, the others can't be found either
+        if out.len() == resolved {
+            let (allergen, ingredients) = ingredients_per_allergen
+                .iter()
+                .filter(|(a, _)| out.iter().all(|(b, _)| b != *a))
+                .min_by_key(|(a, _)| a.to_string())
+                .unwrap();
+            let reason = match ingredients.len() {
+                0 => format!("no ingredient can contain {}", allergen),
+                n => format!("{} is in one of {} ingredients", allergen, n),
+            };
+            return (num_inert, Err(reason));
+        }
+    }
+
+    out.sort_by(|(a1, _), (a2, _)| a1.cmp(a2));
+
+    (num_inert, Ok(out.iter().map(|(_, i)| i).join(",")))
+}
+
+pub fn solve_part1(food: &[Food]) -> usize {
+    solve(food).0
+}
+
+pub fn solve_part2(food: &[Food]) -> Result<String, String> {
+    solve(food).1
+}
+
+/// A list of at least `size` foods with two to eight allergens, each in its own ingredient.
+/// The foods listing an allergen have its ingredient and the one of the allergen before it in
+/// common, so the allergens are found one after the other.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const ALLERGENS: [&str; 9] = [
+        "dairy",
+        "eggs",
+        "fish",
+        "nuts",
+        "peanuts",
+        "sesame",
+        "shellfish",
+        "soy",
+        "wheat",
+    ];
+
+    let count = rng.between(2, 8) as usize;
+    let allergens = rng.sample(&ALLERGENS, count);
+    // the ingredient of allergen `i` is ingredient `i`
+    let mut ingredients: Vec<String> = vec![];
+    while ingredients.len() < count + 20 {
+        let len = rng.between(4, 8) as usize;
+        let name = rng.word("abcdefghijklmnopqrstuvwxyz", len);
+        if !ingredients.contains(&name) {
+            ingredients.push(name);
+        }
+    }
+
+    let food = |rng: &mut Rng, listed: &[usize]| {
+        let mut contents: BTreeSet<usize> =
+            (0..ingredients.len()).filter(|_| rng.chance(0.3)).collect();
+        for &allergen in listed {
+            contents.insert(allergen);
+            contents.extend(allergen.checked_sub(1));
+        }
+        contents
+    };
+
+    let mut foods: Vec<(BTreeSet<usize>, Vec<usize>)> = (0..size.max(2 * count))
+        .map(|f| {
+            let mut listed = vec![f % count];
+            listed.extend((0..count).filter(|&a| a != f % count && rng.chance(0.2)));
+            (food(rng, &listed), listed)
+        })
+        .collect();
+
+    // narrow every allergen down to its ingredient and the one before it
+    for allergen in 0..count {
+        let possible = |foods: &[(BTreeSet<usize>, Vec<usize>)]| {
+            foods
+                .iter()
+                .filter(|(_, listed)| listed.contains(&allergen))
+                .map(|(contents, _)| contents.clone())
+                .reduce(|a, b| &a & &b)
+                .unwrap_or_default()
+        };
+        let extra: BTreeSet<usize> = possible(&foods)
+            .into_iter()
+            .filter(|&i| i != allergen && i + 1 != allergen)
+            .collect();
+        if !extra.is_empty() {
+            let mut contents = food(rng, &[allergen]);
+            contents.retain(|i| !extra.contains(i));
+            foods.push((contents, vec![allergen]));
+        }
+    }
+
+    foods
+        .iter()
+        .map(|(contents, listed)| {
+            let mut names: Vec<&String> = contents.iter().map(|&i| &ingredients[i]).collect();
+            rng.shuffle(&mut names);
+            let mut listed: Vec<&str> = listed.iter().map(|&a| allergens[a]).collect();
+            rng.shuffle(&mut listed);
+            format!(
+                "{} (contains {})\n",
+                names.iter().join(" "),
+                listed.join(", ")
+            )
+        })
+        .collect()
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<Food>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(food: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(food).into())
+    }
+
+    fn part2(food: &Self::Input) -> Result<Answer, String> {
+        solve_part2(food).map(Answer::from)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const EXAMPLE: &str = "\
+        mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
+        trh fvjkl sbzzf mxmxvkd (contains dairy)
+        sqjhc fvjkl (contains soy)
+        sqjhc mxmxvkd sbzzf (contains fish)\
+    ";
+
+    #[test]
+    fn parses_puzzle() {
+        assert_eq!(parse(EXAMPLE).map(|food| food.len()), Ok(4));
+        assert_eq!(
+            parse("sqjhc fvjkl (contains soy\n"),
+            Err(ParseError::new(1, 26, "expected ')'"))
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        let food = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&food), 5);
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let food = parse(&generate(&mut Rng::new(seed), 30)).unwrap();
+            assert!(food.len() >= 30);
+
+            let allergens: HashSet<&String> = food.iter().flat_map(|f| &f.allergens).collect();
+            let (inert, dangerous) = solve(&food);
+            assert!(inert > 0);
+            assert_eq!(dangerous.unwrap().split(',').count(), allergens.len());
+        }
+    }
+
+    #[test]
+    fn reports_unknown_allergens() {
+        let food = parse("a b (contains x)\nc d (contains x)\n").unwrap();
+        assert_eq!(
+            solve_part2(&food),
+            Err("no ingredient can contain x".to_string())
+        );
+        let food = parse("a b (contains x)\n").unwrap();
+        assert_eq!(
+            solve(&food),
+            (0, Err("x is in one of 2 ingredients".to_string()))
+        );
+    }
+}
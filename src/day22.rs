@@ -0,0 +1,564 @@
+//! Day 22: Crab Combat
+
+use crate::error::number;
+use crate::input::paragraphs;
+use crate::observer::{Observer, Silent};
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+
+pub type Deck = VecDeque<usize>;
+
+/// The deck of the given player, `file` is the whole puzzle input
+fn parse_deck(file: &str, input: Option<&str>, player: usize) -> Result<Deck, ParseError> {
+    let header = format!("Player {}:", player);
+    let input = input.ok_or_else(|| ParseError::at_end(file, format!("missing {:?}", header)))?;
+    let mut lines = input.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+
+    match lines.next() {
+        Some(line) if line == header => {}
+        _ => {
+            let reason = format!("expected {:?}", header);
+            return Err(ParseError::at(file, input.trim_start(), reason));
+        }
+    }
+
+    lines.map(|l| number(file, l)).collect()
+}
+
+pub fn parse(file: &str) -> Result<(Deck, Deck), ParseError> {
+    let mut parts = paragraphs(file);
+    Ok((
+        parse_deck(file, parts.next(), 1)?,
+        parse_deck(file, parts.next(), 2)?,
+    ))
+}
+
+/// The decks of the two players
+pub struct Decks<'a>(pub &'a Deck, pub &'a Deck);
+
+impl fmt::Display for Decks<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards = |deck: &Deck| {
+            deck.iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        writeln!(f, "Player 1's deck: {}", cards(self.0))?;
+        write!(f, "Player 2's deck: {}", cards(self.1))
+    }
+}
+
+pub fn score(deck: &Deck) -> usize {
+    deck.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, card)| (i + 1) * card)
+        .sum()
+}
+
+/// What happens in a game of Combat, displayed as in the puzzle description
+pub enum Event<'a> {
+    /// Game `n` of Recursive Combat starts
+    Game(usize),
+    /// The players show their decks and draw the top cards, `game` is `None` in plain Combat
+    Round {
+        game: Option<usize>,
+        round: usize,
+        decks: Decks<'a>,
+        cards: (usize, usize),
+    },
+    /// The round is decided by a sub-game
+    SubGame,
+    /// Back to game `n` after a sub-game
+    Back(usize),
+    RoundWon {
+        game: Option<usize>,
+        round: usize,
+        winner: usize,
+    },
+    GameWon {
+        game: usize,
+        winner: usize,
+    },
+    /// The decks at the end of the game
+    Results(Decks<'a>),
+}
+
+impl fmt::Display for Event<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Game(game) => writeln!(f, "=== Game {} ===", game),
+            Event::Round {
+                game,
+                round,
+                decks,
+                cards,
+            } => {
+                if *round > 1 {
+                    writeln!(f)?;
+                }
+                match game {
+                    Some(game) => writeln!(f, "-- Round {} (Game {}) --", round, game)?,
+                    None => writeln!(f, "-- Round {} --", round)?,
+                }
+                writeln!(f, "{}", decks)?;
+                writeln!(f, "Player 1 plays: {}", cards.0)?;
+                write!(f, "Player 2 plays: {}", cards.1)
+            }
+            Event::SubGame => writeln!(f, "Playing a sub-game to determine the winner..."),
+            Event::Back(game) => write!(f, "\n...anyway, back to game {}.", game),
+            Event::RoundWon {
+                game: Some(game),
+                round,
+                winner,
+            } => write!(
+                f,
+                "Player {} wins round {} of game {}!",
+                winner, round, game
+            ),
+            Event::RoundWon { winner, .. } => write!(f, "Player {} wins the round!", winner),
+            Event::GameWon { game, winner } => {
+                write!(f, "The winner of game {} is player {}!", game, winner)
+            }
+            Event::Results(decks) => write!(f, "\n\n== Post-game results ==\n{}", decks),
+        }
+    }
+}
+
+pub fn solve_part1(p1: &Deck, p2: &Deck) -> usize {
+    solve_part1_with(p1, p2, &mut Silent)
+}
+
+/// Play a game of Combat reporting every round, the population is the number of cards of
+/// player 1
+pub fn solve_part1_with(p1: &Deck, p2: &Deck, observer: &mut dyn Observer) -> usize {
+    let mut p1 = p1.to_owned();
+    let mut p2 = p2.to_owned();
+    let mut rounds = 0;
+
+    for round in 1.. {
+        if p1.is_empty() || p2.is_empty() {
+            break;
+        }
+
+        let (c1, c2) = (p1[0], p2[0]);
+        let decks = Decks(&p1, &p2);
+        observer.event(
+            round,
+            &Event::Round {
+                game: None,
+                round,
+                decks,
+                cards: (c1, c2),
+            },
+        );
+        p1.pop_front();
+        p2.pop_front();
+
+        let winner = if c1 > c2 {
+            // Player 1 wins
+            p1.push_back(c1);
+            p1.push_back(c2);
+            1
+        } else {
+            // Player 2 wins
+            p2.push_back(c2);
+            p2.push_back(c1);
+            2
+        };
+        observer.event(
+            round,
+            &Event::RoundWon {
+                game: None,
+                round,
+                winner,
+            },
+        );
+        observer.step(round, p1.len(), &Decks(&p1, &p2));
+        rounds = round;
+    }
+    observer.event(rounds, &Event::Results(Decks(&p1, &p2)));
+
+    score(&p1) + score(&p2)
+}
+
+/// Play a game of Recursive Combat and return the winner and the winning score
+pub fn combat(p1: &Deck, p2: &Deck) -> (usize, usize) {
+    combat_with(p1, p2, &mut Silent)
+}
+
+/// Like `combat`, reporting every round of every game, the population is the number of cards of
+/// player 1.  The steps count the rounds of all the games in the order they are drawn, so that a
+/// round of a sub-game has its own step.  A round decided by a sub-game is won in the step it
+/// was drawn, after the steps of the sub-game.
+pub fn combat_with(p1: &Deck, p2: &Deck, observer: &mut dyn Observer) -> (usize, usize) {
+    let mut games = 0;
+    let mut steps = 0;
+    let (winner, p1, p2) = game(p1, p2, &mut games, &mut steps, observer);
+    observer.event(steps, &Event::Results(Decks(&p1, &p2)));
+
+    (winner, score(&p1) + score(&p2))
+}
+
+/// Play game number `games + 1` of Recursive Combat, returning the winner and the final decks.
+/// `games` counts the games and `steps` the rounds played, sub-games included.
+fn game(
+    p1: &Deck,
+    p2: &Deck,
+    games: &mut usize,
+    steps: &mut usize,
+    observer: &mut dyn Observer,
+) -> (usize, Deck, Deck) {
+    let mut p1 = p1.to_owned();
+    let mut p2 = p2.to_owned();
+    *games += 1;
+    let game = *games;
+    observer.event(*steps + 1, &Event::Game(game));
+
+    let mut prev_states: HashSet<(Deck, Deck)> = HashSet::new();
+
+    for round in 1.. {
+        if p1.is_empty() || p2.is_empty() {
+            break;
+        }
+
+        if !prev_states.insert((p1.to_owned(), p2.to_owned())) {
+            // Same configuration as before, player 1 wins.
+            p2.clear();
+            break;
+        }
+
+        // Players draw from the top of the deck
+        *steps += 1;
+        let step = *steps;
+        let (c1, c2) = (p1[0], p2[0]);
+        let decks = Decks(&p1, &p2);
+        let game_no = Some(game);
+        observer.event(
+            step,
+            &Event::Round {
+                game: game_no,
+                round,
+                decks,
+                cards: (c1, c2),
+            },
+        );
+        p1.pop_front();
+        p2.pop_front();
+
+        let winner = if c1 <= p1.len() && c2 <= p2.len() {
+            // Both players have at least as many cards remaining in
+            // their deck as the value of the card they just drew
+            observer.event(step, &Event::SubGame);
+            let winner = self::game(
+                &p1.iter().take(c1).cloned().collect(),
+                &p2.iter().take(c2).cloned().collect(),
+                games,
+                steps,
+                observer,
+            )
+            .0;
+            observer.event(step, &Event::Back(game));
+            winner
+        } else {
+            // Otherwise the winner of the round is the player with the
+            // higher-value card
+            if c1 > c2 {
+                1
+            } else {
+                2
+            }
+        };
+
+        if winner == 1 {
+            // Player 1 wins
+            p1.push_back(c1);
+            p1.push_back(c2);
+        } else {
+            // Player 2 wins
+            p2.push_back(c2);
+            p2.push_back(c1);
+        }
+        observer.event(
+            step,
+            &Event::RoundWon {
+                game: game_no,
+                round,
+                winner,
+            },
+        );
+        observer.step(step, p1.len(), &Decks(&p1, &p2));
+    }
+
+    let winner = if p1.is_empty() { 2 } else { 1 };
+    observer.event(*steps, &Event::GameWon { game, winner });
+
+    (winner, p1, p2)
+}
+
+pub fn solve_part2(p1: &Deck, p2: &Deck) -> usize {
+    combat(p1, p2).1
+}
+
+/// Two decks of `size` cards each, at least one, dealt from the cards 1 to `2 * size`.  The
+/// cards are dealt again until a game of Combat ends within 10000 rounds.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    loop {
+        let mut cards: Vec<usize> = (1..=2 * size).collect();
+        rng.shuffle(&mut cards);
+        let (mut p1, mut p2): (Deck, Deck) = (
+            cards[..size].iter().copied().collect(),
+            cards[size..].iter().copied().collect(),
+        );
+
+        for _ in 0..10_000 {
+            if p1.is_empty() || p2.is_empty() {
+                let deck = |cards: &[usize]| -> String {
+                    cards.iter().map(|c| format!("{}\n", c)).collect()
+                };
+                let (deck1, deck2) = (deck(&cards[..size]), deck(&cards[size..]));
+                return format!("Player 1:\n{}\nPlayer 2:\n{}", deck1, deck2);
+            }
+
+            let (c1, c2) = (p1.pop_front().unwrap(), p2.pop_front().unwrap());
+            if c1 > c2 {
+                p1.extend([c1, c2]);
+            } else {
+                p2.extend([c2, c1]);
+            }
+        }
+    }
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = (Deck, Deck);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(decks: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(&decks.0, &decks.1).into())
+    }
+
+    fn part2(decks: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(&decks.0, &decks.1).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn solve_with(
+        decks: &Self::Input,
+        part: u8,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<Answer, String>> {
+        match part {
+            1 => Some(Ok(solve_part1_with(&decks.0, &decks.1, observer).into())),
+            2 => Some(Ok(combat_with(&decks.0, &decks.1, observer).1.into())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observer::Trace;
+    const EXAMPLE: &str = "\
+        Player 1:
+        9
+        2
+        6
+        3
+        1
+
+        Player 2:
+        5
+        8
+        4
+        7
+        10\
+    ";
+
+    const EXAMPLE2: &str = "\
+        Player 1:
+        43
+        19
+
+        Player 2:
+        2
+        29
+        14\
+    ";
+
+    #[test]
+    fn parses_puzzle() {
+        let (p1, p2) = parse(EXAMPLE2).unwrap();
+        assert_eq!(p1, vec![43, 19]);
+        assert_eq!(p2, vec![2, 29, 14]);
+        assert_eq!(
+            parse("Player 1:\r\n43\r\n  \r\nPlayer 2:\r\n2"),
+            Ok((Deck::from(vec![43]), Deck::from(vec![2])))
+        );
+
+        assert_eq!(
+            parse("Player 1:\n43\n19"),
+            Err(ParseError::new(3, 3, "missing \"Player 2:\""))
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        let (p1, p2) = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&p1, &p2), 306);
+    }
+
+    #[test]
+    fn reports_rounds() {
+        let (p1, p2) = parse(EXAMPLE).unwrap();
+        let mut rounds = vec![];
+        let mut observer =
+            |round, cards, decks: &dyn fmt::Display| rounds.push((round, cards, decks.to_string()));
+        solve_part1_with(&p1, &p2, &mut observer);
+
+        assert_eq!(rounds.len(), 29);
+        assert_eq!(
+            rounds[0],
+            (
+                1,
+                6,
+                "Player 1's deck: 2, 6, 3, 1, 9, 5\nPlayer 2's deck: 8, 4, 7, 10".to_string()
+            )
+        );
+        assert_eq!(rounds[28].1, 0);
+    }
+
+    #[test]
+    fn traces_games() {
+        let (p1, p2) = parse(EXAMPLE).unwrap();
+        let mut trace = Trace::new(vec![]);
+        combat_with(&p1, &p2, &mut trace);
+        let trace = String::from_utf8(trace.into_inner()).unwrap();
+
+        assert!(trace.starts_with(
+            "\
+=== Game 1 ===
+
+-- Round 1 (Game 1) --
+Player 1's deck: 9, 2, 6, 3, 1
+Player 2's deck: 5, 8, 4, 7, 10
+Player 1 plays: 9
+Player 2 plays: 5
+Player 1 wins round 1 of game 1!
+
+-- Round 2 (Game 1) --
+"
+        ));
+        assert!(trace.contains(
+            "\
+-- Round 9 (Game 1) --
+Player 1's deck: 4, 9, 8, 5, 2
+Player 2's deck: 3, 10, 1, 7, 6
+Player 1 plays: 4
+Player 2 plays: 3
+Playing a sub-game to determine the winner...
+
+=== Game 2 ===
+
+-- Round 1 (Game 2) --
+Player 1's deck: 9, 8, 5, 2
+Player 2's deck: 10, 1, 7
+Player 1 plays: 9
+Player 2 plays: 10
+Player 2 wins round 1 of game 2!
+"
+        ));
+        assert!(trace.contains(
+            "\
+The winner of game 2 is player 2!
+
+...anyway, back to game 1.
+Player 2 wins round 9 of game 1!
+
+-- Round 10 (Game 1) --
+"
+        ));
+        assert!(trace.ends_with(
+            "\
+The winner of game 1 is player 2!
+
+
+== Post-game results ==
+Player 1's deck: \nPlayer 2's deck: 7, 5, 6, 2, 4, 1, 10, 8, 9, 3
+"
+        ));
+    }
+
+    #[test]
+    fn numbers_steps_across_games() {
+        let (p1, p2) = parse(EXAMPLE).unwrap();
+        let trace_steps = |steps| {
+            let mut trace = Trace::new(vec![]).steps(steps);
+            combat_with(&p1, &p2, &mut trace);
+            String::from_utf8(trace.into_inner()).unwrap()
+        };
+
+        assert_eq!(
+            trace_steps(1..=1),
+            "\
+=== Game 1 ===
+
+-- Round 1 (Game 1) --
+Player 1's deck: 9, 2, 6, 3, 1
+Player 2's deck: 5, 8, 4, 7, 10
+Player 1 plays: 9
+Player 2 plays: 5
+Player 1 wins round 1 of game 1!
+"
+        );
+        // round 9 of game 1 is decided by game 2, whose first round is step 10
+        assert_eq!(
+            trace_steps(10..=10),
+            "\
+=== Game 2 ===
+
+-- Round 1 (Game 2) --
+Player 1's deck: 9, 8, 5, 2
+Player 2's deck: 10, 1, 7
+Player 1 plays: 9
+Player 2 plays: 10
+Player 2 wins round 1 of game 2!
+"
+        );
+    }
+
+    #[test]
+    fn avoids_infinite_loop() {
+        let (p1, p2) = parse(EXAMPLE2).unwrap();
+        assert_eq!(solve_part2(&p1, &p2), 105);
+    }
+
+    #[test]
+    fn solves_part2() {
+        let (p1, p2) = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part2(&p1, &p2), 291);
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let (p1, p2) = parse(&generate(&mut Rng::new(seed), 10)).unwrap();
+            assert_eq!((p1.len(), p2.len()), (10, 10));
+            assert!(solve_part1(&p1, &p2) > 0);
+            assert!(solve_part2(&p1, &p2) > 0);
+        }
+    }
+}
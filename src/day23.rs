@@ -0,0 +1,435 @@
+//! Day 23: Crab Cups
+
+use crate::differential::{compare, Disagreement};
+use crate::observer::{Observer, Silent};
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The cups in clockwise order from the current cup, which is in parentheses
+pub struct Circle<F: Fn(usize) -> usize> {
+    pub current: usize,
+    pub len: usize,
+    /// The cup clockwise of the given one
+    pub next: F,
+}
+
+impl<F: Fn(usize) -> usize> fmt::Display for Circle<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cups: ({})", self.current)?;
+        let mut cup = self.current;
+        for _ in 1..self.len {
+            cup = (self.next)(cup);
+            write!(f, " {}", cup)?;
+        }
+        Ok(())
+    }
+}
+
+/// A move of the crab: the cups before the move, the three cups picked up and the destination
+/// cup, displayed as in the puzzle description
+pub struct Move<'a> {
+    pub round: usize,
+    pub cups: &'a dyn fmt::Display,
+    pub picked: [usize; 3],
+    pub destination: usize,
+}
+
+impl<'a> Move<'a> {
+    fn new(round: usize, cups: &'a dyn fmt::Display, picked: &[usize], destination: usize) -> Self {
+        Self {
+            round,
+            cups,
+            picked: [picked[0], picked[1], picked[2]],
+            destination,
+        }
+    }
+}
+
+impl fmt::Display for Move<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c] = self.picked;
+        writeln!(f, "-- move {} --", self.round)?;
+        writeln!(f, "{}", self.cups)?;
+        writeln!(f, "pick up: {}, {}, {}", a, b, c)?;
+        writeln!(f, "destination: {}", self.destination)
+    }
+}
+
+/// Play the given number of rounds and return the final arrangement of the cups
+pub fn play(cups: &[usize], rounds: usize) -> Vec<usize> {
+    play_with(cups, rounds, &mut Silent)
+}
+
+/// Like `play`, reporting the cups after every round, the population is the number of cups
+pub fn play_with(cups: &[usize], rounds: usize, observer: &mut dyn Observer) -> Vec<usize> {
+    let max = *cups.iter().max().unwrap();
+    let mut cups = cups.to_owned();
+    let mut current = cups[0];
+
+    for round in 1..=rounds {
+        let taken_cups: Vec<usize> = cups
+            .iter()
+            .cycle()
+            .skip_while(|x| **x != current)
+            .skip(1)
+            .take(3)
+            .cloned()
+            .collect();
+
+        // find the destination cup, which is not taken
+        let mut destination = if current > 1 { current - 1 } else { max };
+        while taken_cups.contains(&destination) {
+            destination = if destination > 1 {
+                destination - 1
+            } else {
+                max
+            };
+        }
+
+        let next = |cup| {
+            let i = cups.iter().position(|&c| c == cup).unwrap();
+            cups[(i + 1) % cups.len()]
+        };
+        let circle = Circle {
+            current,
+            len: cups.len(),
+            next,
+        };
+        observer.event(round, &Move::new(round, &circle, &taken_cups, destination));
+
+        // remove taken cups and insert them after the destination
+        cups.retain(|x| !taken_cups.contains(x));
+        let pos = cups.iter().position(|&x| x == destination).unwrap();
+        cups.splice(pos + 1..pos + 1, taken_cups);
+
+        current = *cups
+            .iter()
+            .cycle()
+            .skip_while(|x| **x != current)
+            .nth(1)
+            .unwrap();
+
+        let next = |cup| {
+            let i = cups.iter().position(|&c| c == cup).unwrap();
+            cups[(i + 1) % cups.len()]
+        };
+        observer.step(
+            round,
+            cups.len(),
+            &Circle {
+                current,
+                len: cups.len(),
+                next,
+            },
+        );
+    }
+
+    cups
+}
+
+pub fn solve_part1(cups: &[usize]) -> String {
+    solve_part1_with(cups, &mut Silent)
+}
+
+pub fn solve_part1_with(cups: &[usize], observer: &mut dyn Observer) -> String {
+    play_with(cups, 100, observer)
+        .iter()
+        .cycle()
+        .skip_while(|x| **x != 1)
+        .skip(1)
+        .take(cups.len() - 1)
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+/// Play the given number of rounds and return the two cups after cup 1
+pub fn play_v2(cups: &[usize], rounds: usize) -> Vec<usize> {
+    play_v2_with(cups, rounds, &mut Silent)
+}
+
+/// Like `play_v2`, reporting the cups after every round, the population is the number of cups
+pub fn play_v2_with(cups: &[usize], rounds: usize, observer: &mut dyn Observer) -> Vec<usize> {
+    let n = cups.len();
+    let mut next: HashMap<usize, usize> = cups.windows(2).map(|w| (w[0], w[1])).collect();
+    next.insert(cups[n - 1], cups[0]);
+
+    let mut current = cups[0];
+
+    for round in 1..=rounds {
+        let mut pick: Vec<usize> = vec![next[&current]];
+        for _ in 0..2 {
+            pick.push(next[pick.iter().last().unwrap()]);
+        }
+
+        let mut destination = if current > 1 { current - 1 } else { n };
+
+        while pick.contains(&destination) {
+            destination = if destination > 1 { destination - 1 } else { n }
+        }
+
+        let circle = Circle {
+            current,
+            len: n,
+            next: |cup| next[&cup],
+        };
+        observer.event(round, &Move::new(round, &circle, &pick, destination));
+
+        next.insert(current, next[&pick[2]]);
+        next.insert(pick[2], next[&destination]);
+        next.insert(destination, pick[0]);
+
+        current = next[&current];
+
+        let next = |cup| next[&cup];
+        observer.step(
+            round,
+            n,
+            &Circle {
+                current,
+                len: n,
+                next,
+            },
+        );
+    }
+
+    let mut res: Vec<usize> = vec![next[&1]];
+    for _ in 0..1 {
+        res.push(next[res.iter().last().unwrap()]);
+    }
+
+    res
+}
+
+pub fn solve_part2(cups: &[usize]) -> usize {
+    solve_part2_with(cups, &mut Silent)
+}
+
+pub fn solve_part2_with(cups: &[usize], observer: &mut dyn Observer) -> usize {
+    let mut cups = cups.to_owned();
+    for c in cups.len() + 1..=1_000_000 {
+        cups.push(c);
+    }
+
+    assert_eq!(cups.len(), 1_000_000);
+    assert_eq!(cups.iter().max(), Some(&1_000_000));
+
+    play_v2_with(&cups, 10_000_000, observer)
+        .iter()
+        .take(2)
+        .product()
+}
+
+/// The labels of the cups in clockwise order, one digit per cup
+pub fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    let line = input.trim();
+    let cups = line
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(10)
+                .map(|d| d as usize)
+                .filter(|&d| d >= 1 && d <= line.len())
+                .ok_or_else(|| ParseError::at(input, &line[i..], "invalid cup label"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (i, cup) in cups.iter().enumerate() {
+        if cups[..i].contains(cup) {
+            return Err(ParseError::at(input, &line[i..], "duplicate cup label"));
+        }
+    }
+
+    // the three cups picked up, the current cup and at least one destination
+    if cups.len() < 5 {
+        return Err(ParseError::at(input, line, "expected at least 5 cups"));
+    }
+
+    Ok(cups)
+}
+
+/// The cups labelled 1 to `size`, between 5 and 9, in a random order
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut cups: Vec<usize> = (1..=size.clamp(5, 9)).collect();
+    rng.shuffle(&mut cups);
+    cups.iter().map(|cup| cup.to_string()).collect::<String>() + "\n"
+}
+
+/// The cups and the number of rounds
+type Game = (Vec<usize>, usize);
+
+/// Five to nine cups in a random order, played for up to 100 rounds
+fn game(rng: &mut Rng) -> Game {
+    let mut cups: Vec<usize> = (1..=rng.between(5, 9) as usize).collect();
+    rng.shuffle(&mut cups);
+    let rounds = rng.between(0, 100) as usize;
+    (cups, rounds)
+}
+
+/// The games with fewer rounds or without the highest cup
+fn simpler((cups, rounds): &Game) -> Vec<Game> {
+    let mut games = vec![];
+
+    if *rounds > 0 {
+        games.push((cups.clone(), rounds / 2));
+        games.push((cups.clone(), rounds - 1));
+    }
+    if cups.len() > 5 {
+        let fewer = cups.iter().copied().filter(|&c| c != cups.len()).collect();
+        games.push((fewer, *rounds));
+    }
+
+    games
+}
+
+/// The two cups after cup 1 of an arrangement
+fn after_one(cups: &[usize]) -> Vec<usize> {
+    let one = cups.iter().position(|&c| c == 1).unwrap();
+    (1..=2).map(|i| cups[(one + i) % cups.len()]).collect()
+}
+
+/// Compare `play` and `play_v2` on `cases` random games
+pub fn compare_versions(rng: &mut Rng, cases: usize) -> Option<Disagreement<Game, Vec<usize>>> {
+    compare(
+        rng,
+        cases,
+        game,
+        simpler,
+        |(cups, rounds)| after_one(&play(cups, *rounds)),
+        |(cups, rounds)| play_v2(cups, *rounds),
+    )
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(cups: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(cups).into())
+    }
+
+    fn part2(cups: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(cups).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    fn solve_with(
+        cups: &Self::Input,
+        part: u8,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<Answer, String>> {
+        match part {
+            1 => Some(Ok(solve_part1_with(cups, observer).into())),
+            2 => Some(Ok(solve_part2_with(cups, observer).into())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observer::Trace;
+
+    #[test]
+    fn parses_puzzle() {
+        assert_eq!(parse("389125467\n"), Ok(vec![3, 8, 9, 1, 2, 5, 4, 6, 7]));
+        assert_eq!(
+            parse("3891254676"),
+            Err(ParseError::new(1, 10, "duplicate cup label"))
+        );
+        assert_eq!(
+            parse("3120"),
+            Err(ParseError::new(1, 4, "invalid cup label"))
+        );
+        assert_eq!(
+            parse("123\n"),
+            Err(ParseError::new(1, 1, "expected at least 5 cups"))
+        );
+        assert_eq!(
+            parse("\n"),
+            Err(ParseError::new(1, 1, "expected at least 5 cups"))
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        let cups = [3, 8, 9, 1, 2, 5, 4, 6, 7];
+        assert_eq!(play(&cups, 10), vec![5, 8, 3, 7, 4, 1, 9, 2, 6]);
+        assert_eq!(solve_part1(&cups), "67384529");
+    }
+
+    #[test]
+    fn test_play_v2() {
+        let cups = [3, 8, 9, 1, 2, 5, 4, 6, 7];
+        assert_eq!(play_v2(&cups, 1), vec![5, 4]);
+        assert_eq!(play_v2(&cups, 10), vec![9, 2]);
+        assert_eq!(after_one(&play(&cups, 10)), vec![9, 2]);
+    }
+
+    #[test]
+    fn shrinks_games() {
+        assert_eq!(
+            simpler(&(vec![3, 1, 6, 2, 5, 4], 3)),
+            [
+                (vec![3, 1, 6, 2, 5, 4], 1),
+                (vec![3, 1, 6, 2, 5, 4], 2),
+                (vec![3, 1, 2, 5, 4], 3)
+            ]
+        );
+        assert_eq!(compare_versions(&mut Rng::new(1), 100), None);
+    }
+
+    #[test]
+    fn reports_rounds() {
+        let cups = [3, 8, 9, 1, 2, 5, 4, 6, 7];
+        let mut v1 = vec![];
+        let mut v2 = vec![];
+        play_with(&cups, 10, &mut |_, _, circle: &dyn fmt::Display| {
+            v1.push(circle.to_string())
+        });
+        play_v2_with(&cups, 10, &mut |_, _, circle: &dyn fmt::Display| {
+            v2.push(circle.to_string())
+        });
+
+        assert_eq!(v1[0], "cups: (2) 8 9 1 5 4 6 7 3");
+        assert_eq!(v1[9], "cups: (8) 3 7 4 1 9 2 6 5");
+        assert_eq!(v1, v2);
+    }
+
+    #[test]
+    fn traces_moves() {
+        let cups = [3, 8, 9, 1, 2, 5, 4, 6, 7];
+        let mut v1 = Trace::new(vec![]).steps(2..=2);
+        let mut v2 = Trace::new(vec![]).steps(2..=2);
+        play_with(&cups, 10, &mut v1);
+        play_v2_with(&cups, 10, &mut v2);
+
+        let v1 = String::from_utf8(v1.into_inner()).unwrap();
+        assert_eq!(
+            v1,
+            "-- move 2 --\ncups: (2) 8 9 1 5 4 6 7 3\npick up: 8, 9, 1\ndestination: 7\n\n"
+        );
+        assert_eq!(String::from_utf8(v2.into_inner()).unwrap(), v1);
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..10 {
+            let cups = parse(&generate(&mut Rng::new(seed), 9)).unwrap();
+            assert_eq!(cups.len(), 9);
+            let mut labels: Vec<char> = solve_part1(&cups).chars().collect();
+            labels.sort_unstable();
+            assert_eq!(labels, ['2', '3', '4', '5', '6', '7', '8', '9']);
+        }
+    }
+}
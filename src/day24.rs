@@ -0,0 +1,272 @@
+//! Day 24: Lobby Layout
+
+use crate::automaton::{Adjacent, Automaton, HexGrid, Rule};
+use crate::error::parse_lines;
+use crate::observer::{Observer, Silent};
+use crate::random::Rng;
+use crate::repl::Reply;
+use crate::{Answer, ParseError, Solution};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+pub type Coords = (i32, i32);
+
+pub fn parse(input: &str) -> Result<Vec<Coords>, ParseError> {
+    parse_lines(input, parse_line)
+}
+
+/// The coordinates of the tile at the end of the path
+pub fn parse_line(input: &str) -> Result<Coords, ParseError> {
+    let mut rest = input.trim();
+
+    let mut x = 0;
+    let mut y = 0;
+    while !rest.is_empty() {
+        let direction = ["se", "sw", "nw", "ne", "e", "w"]
+            .iter()
+            .find(|d| rest.starts_with(*d))
+            .ok_or_else(|| ParseError::at(input, rest, "unexpected direction"))?;
+
+        match *direction {
+            "e" => x += 1,
+            "se" => {
+                x += 1;
+                y -= 1;
+            }
+            "sw" => y -= 1,
+            "w" => x -= 1,
+            "nw" => {
+                x -= 1;
+                y += 1;
+            }
+            "ne" => {
+                y += 1;
+            }
+            _ => unreachable!(),
+        }
+        rest = &rest[direction.len()..];
+    }
+    Ok((x, y))
+}
+
+/// The tiles flipped an odd number of times
+pub fn black_tiles(tiles: &[Coords]) -> HashSet<Coords> {
+    let mut count: HashMap<Coords, usize> = HashMap::new();
+    for &tile in tiles {
+        *count.entry(tile).or_insert(0) += 1;
+    }
+
+    count
+        .iter()
+        .filter_map(|(coords, &state)| if state % 2 == 1 { Some(coords) } else { None })
+        .cloned()
+        .collect()
+}
+
+/// The black tiles `#` and white tiles `.` of the floor, north up
+pub struct Floor<'a>(pub &'a HashSet<Coords>);
+
+impl fmt::Display for Floor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // every row is shifted by half a tile from the one below
+        let column = |&(x, y): &Coords| 2 * x + y;
+        let columns = self.0.iter().map(column);
+        let (min_col, max_col) = (columns.clone().min(), columns.max());
+        let rows = self.0.iter().map(|&(_, y)| y);
+        let (min_row, max_row) = (rows.clone().min(), rows.max());
+
+        if let (Some(min_col), Some(max_col), Some(min_row), Some(max_row)) =
+            (min_col, max_col, min_row, max_row)
+        {
+            for y in (min_row..=max_row).rev() {
+                let row: String = (min_col..=max_col)
+                    .map(|col| match (col - y) % 2 {
+                        0 if self.0.contains(&((col - y) / 2, y)) => '#',
+                        0 => '.',
+                        _ => ' ',
+                    })
+                    .collect();
+                writeln!(f, "{}", row.trim_end())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn solve_part1(tiles: &[Coords]) -> usize {
+    black_tiles(tiles).len()
+}
+
+pub fn solve_part2(tiles: &[Coords], steps: usize) -> usize {
+    solve_part2_with(tiles, steps, &mut Silent)
+}
+
+/// Like `solve_part2`, reporting the floor after every day, the population is the number of
+/// black tiles
+pub fn solve_part2_with(tiles: &[Coords], steps: usize, observer: &mut dyn Observer) -> usize {
+    // a white tile with two black neighbours turns black, a black tile with none or more than
+    // two black neighbours turns white
+    let rule = Rule::new(&[2], &[1, 2]);
+    let mut floor = Automaton::new(HexGrid, Adjacent, rule, black_tiles(tiles));
+    floor.run(steps, |a| {
+        observer.step(a.generation(), a.population(), &Floor(a.alive()))
+    });
+
+    floor.population()
+}
+
+/// `size` paths of one to twenty steps
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let path: String = (0..rng.between(1, 20))
+                .map(|_| *rng.choose(&["e", "se", "sw", "w", "nw", "ne"]))
+                .collect();
+            path + "\n"
+        })
+        .collect()
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Vec<Coords>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(tiles: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(tiles).into())
+    }
+
+    fn part2(tiles: &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part2(tiles, 100).into())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    const QUERIES: &'static [(&'static str, &'static str)] =
+        &[("show", "The black tiles before the first day")];
+
+    fn query(tiles: &Self::Input, query: &str, _args: &[&str]) -> Option<Reply> {
+        match query {
+            "show" => Some(Ok(Floor(&black_tiles(tiles)).to_string())),
+            _ => None,
+        }
+    }
+
+    fn solve_with(
+        tiles: &Self::Input,
+        part: u8,
+        observer: &mut dyn Observer,
+    ) -> Option<Result<Answer, String>> {
+        match part {
+            1 => Some(Ok(solve_part1(tiles).into())),
+            2 => Some(Ok(solve_part2_with(tiles, 100, observer).into())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::Neighbourhood;
+
+    const EXAMPLE: &str = "\
+        sesenwnenenewseeswwswswwnenewsewsw
+        neeenesenwnwwswnenewnwwsewnenwseswesw
+        seswneswswsenwwnwse
+        nwnwneseeswswnenewneswwnewseswneseene
+        swweswneswnenwsewnwneneseenw
+        eesenwseswswnenwswnwnwsewwnwsene
+        sewnenenenesenwsewnenwwwse
+        wenwwweseeeweswwwnwwe
+        wsweesenenewnwwnwsenewsenwwsesesenwne
+        neeswseenwwswnwswswnw
+        nenwswwsewswnenenewsenwsenwnesesenew
+        enewnwewneswsewnwswenweswnenwsenwsw
+        sweneswneswneneenwnewenewwneswswnese
+        swwesenesewenwneswnwwneseswwne
+        enesenwswwswneneswsenwnewswseenwsese
+        wnwnesenesenenwwnenwsewesewsesesew
+        nenewswnwewswnenesenwnesewesw
+        eneswnwswnwsenenwnwnwwseeswneewsenese
+        neswnwewnwnwseenwseesewsenwsweewe
+        wseweeenwnesenwwwswnew\
+    ";
+
+    #[test]
+    fn parses_puzzle() {
+        assert_eq!(parse_line("esew"), Ok((1, -1))); // se neighbour of the reference tile
+        assert_eq!(parse_line("nwwswee"), Ok((0, 0)));
+        assert_eq!(
+            parse("esew\nnwwsnee"),
+            Err(ParseError::new(2, 4, "unexpected direction"))
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        let tiles = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part1(&tiles), 10);
+    }
+
+    #[test]
+    fn solves_part2() {
+        let tiles = parse(EXAMPLE).unwrap();
+        assert_eq!(solve_part2(&tiles, 1), 15);
+        assert_eq!(solve_part2(&tiles, 10), 37);
+    }
+
+    #[test]
+    fn reports_days() {
+        let tiles = parse(EXAMPLE).unwrap();
+        let mut days = vec![];
+        solve_part2_with(&tiles, 3, &mut |day, black, _: &dyn fmt::Display| {
+            days.push((day, black))
+        });
+        assert_eq!(days, [(1, 15), (2, 12), (3, 25)]);
+
+        let floor: HashSet<Coords> = [(0, 0), (1, 0), (0, 1)].iter().copied().collect();
+        assert_eq!(Floor(&floor).to_string(), " #\n# #\n");
+        assert_eq!(
+            Day24::query(&tiles, "show", &[]),
+            Some(Ok(Floor(&black_tiles(&tiles)).to_string()))
+        );
+    }
+
+    #[test]
+    fn count_neigbours() {
+        let mut near = Adjacent.neighbours(&HexGrid, &(0, 0));
+        near.sort();
+        assert_eq!(
+            near,
+            vec![
+                (-1, 0), // w
+                (-1, 1), // nw
+                (0, -1), // sw
+                (0, 1),  // ne
+                (1, -1), // se
+                (1, 0)   // e
+            ]
+        );
+        for line in ["e", "se", "sw", "w", "nw", "ne"].iter() {
+            assert!(near.contains(&parse_line(line).unwrap()));
+        }
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..5 {
+            let tiles = parse(&generate(&mut Rng::new(seed), 50)).unwrap();
+            assert_eq!(tiles.len(), 50);
+            assert!(solve_part1(&tiles) <= 50);
+            solve_part2(&tiles, 10);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Day 25: Combo Breaker
+
+use crate::error::number;
+use crate::modular::{discrete_log, pow_mod};
+use crate::random::Rng;
+use crate::{Answer, ParseError, Solution};
+
+const MODULUS: usize = 20201227;
+const SUBJECT: usize = 7;
+
+pub fn transform(pk: usize, loop_size: usize) -> usize {
+    pow_mod(pk as u64, loop_size as u64, MODULUS as u64) as usize
+}
+
+/// The loop size transforming the subject number 7 into the public key `pk`.  7 is a
+/// primitive root modulo 20201227, so every public key has one.
+pub fn find_loop_size(pk: usize) -> usize {
+    discrete_log(SUBJECT as u64, pk as u64, MODULUS as u64).expect("7 generates every public key")
+        as usize
+}
+
+pub fn solve_part1(door_pk: usize, card_pk: usize) -> usize {
+    let door_loop_size = find_loop_size(door_pk);
+
+    transform(card_pk, door_loop_size)
+}
+
+/// The card's and the door's public keys
+pub fn parse(input: &str) -> Result<(usize, usize), ParseError> {
+    let mut lines = input.lines().map(|line| line.trim());
+    let mut key = || {
+        let line = lines
+            .next()
+            .ok_or_else(|| ParseError::at_end(input, "missing public key"))?;
+        match number(input, line)? {
+            pk if pk > 0 && pk < MODULUS => Ok(pk),
+            _ => Err(ParseError::at(input, line, "public key out of range")),
+        }
+    };
+
+    let card_pk = key()?;
+    let door_pk = key()?;
+
+    Ok((card_pk, door_pk))
+}
+
+/// The public keys of a card and a door with random loop sizes, whatever the `size`
+pub fn generate(rng: &mut Rng, _size: usize) -> String {
+    let mut key = || transform(SUBJECT, rng.between(1, MODULUS as i64 - 2) as usize);
+    format!("{}\n{}\n", key(), key())
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = (usize, usize);
+
+    const PARTS: u8 = 1;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(&(card_pk, door_pk): &Self::Input) -> Result<Answer, String> {
+        Ok(solve_part1(door_pk, card_pk).into())
+    }
+
+    fn part2(_keys: &Self::Input) -> Result<Answer, String> {
+        unreachable!("Day 25 has only one part")
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        assert_eq!(transform(7, 8), 5764801);
+        assert_eq!(transform(7, 11), 17807724);
+    }
+
+    #[test]
+    fn parses_puzzle() {
+        assert_eq!(parse("5764801\n17807724\n"), Ok((5764801, 17807724)));
+        assert_eq!(
+            parse("5764801\n"),
+            Err(ParseError::new(2, 1, "missing public key"))
+        );
+        assert_eq!(
+            parse("5764801\n20201227"),
+            Err(ParseError::new(2, 1, "public key out of range"))
+        );
+    }
+
+    #[test]
+    fn solves_part1() {
+        assert_eq!(solve_part1(17807724, 5764801), 14897079);
+    }
+
+    #[test]
+    fn generates_inputs() {
+        for seed in 0..5 {
+            let (card_pk, door_pk) = parse(&generate(&mut Rng::new(seed), 0)).unwrap();
+            assert_eq!(
+                solve_part1(door_pk, card_pk),
+                transform(door_pk, find_loop_size(card_pk))
+            );
+        }
+    }
+}
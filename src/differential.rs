@@ -0,0 +1,178 @@
+//! Differential testing: two implementations of the same function run on many random inputs
+//!
+//! The first input on which they disagree is shrunk: as long as a simpler variant of the input
+//! still makes them disagree, the variant replaces it.  The result is a small input to debug, a
+//! local minimum rather than necessarily the smallest one.
+
+use crate::random::Rng;
+use crate::{day15, day18, day23};
+use std::fmt;
+
+/// An input on which two implementations disagree, with both results
+#[derive(Clone, Debug, PartialEq)]
+pub struct Disagreement<T, U> {
+    pub input: T,
+    pub left: U,
+    pub right: U,
+    /// The number of the random input which first showed the disagreement, from 1
+    pub case: usize,
+    /// The number of times the input was simplified
+    pub shrinks: usize,
+}
+
+impl<T: fmt::Debug, U: fmt::Debug> fmt::Display for Disagreement<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} gives {:?} and {:?} (case {}, shrunk {} times)",
+            self.input, self.left, self.right, self.case, self.shrinks
+        )
+    }
+}
+
+/// Run `left` and `right` on `cases` inputs from `generate` and return the first disagreement,
+/// shrunk with `shrink`, which lists the simpler variants of an input
+pub fn compare<T, U: PartialEq>(
+    rng: &mut Rng,
+    cases: usize,
+    generate: impl Fn(&mut Rng) -> T,
+    shrink: impl Fn(&T) -> Vec<T>,
+    left: impl Fn(&T) -> U,
+    right: impl Fn(&T) -> U,
+) -> Option<Disagreement<T, U>> {
+    let disagree = |input: &T| {
+        let (l, r) = (left(input), right(input));
+        if l != r {
+            Some((l, r))
+        } else {
+            None
+        }
+    };
+
+    for case in 1..=cases {
+        let input = generate(rng);
+        if let Some((l, r)) = disagree(&input) {
+            let mut found = Disagreement {
+                input,
+                left: l,
+                right: r,
+                case,
+                shrinks: 0,
+            };
+
+            while let Some((input, (l, r))) = shrink(&found.input)
+                .into_iter()
+                .find_map(|simpler| disagree(&simpler).map(|results| (simpler, results)))
+            {
+                found.input = input;
+                found.left = l;
+                found.right = r;
+                found.shrinks += 1;
+            }
+
+            return Some(found);
+        }
+    }
+
+    None
+}
+
+/// A day with two implementations of the same function
+pub struct Pair {
+    pub day: u8,
+    /// The names of the implementations
+    pub names: (&'static str, &'static str),
+    compare: fn(&mut Rng, usize) -> Option<String>,
+}
+
+impl Pair {
+    /// Compare the implementations on `cases` random inputs from `seed`, the shrunk
+    /// disagreement if they don't agree on all of them
+    pub fn compare(&self, seed: u64, cases: usize) -> Option<String> {
+        (self.compare)(&mut Rng::new(seed), cases)
+    }
+}
+
+/// All the pairs of implementations in calendar order
+pub const PAIRS: [Pair; 3] = [
+    Pair {
+        day: 15,
+        names: ("last_number_v1", "last_number_v2"),
+        compare: |rng, cases| day15::compare_versions(rng, cases).map(|d| d.to_string()),
+    },
+    Pair {
+        day: 18,
+        names: ("Exp::v1", "Exp::v2"),
+        compare: |rng, cases| day18::compare_versions(rng, cases).map(|d| d.to_string()),
+    },
+    Pair {
+        day: 23,
+        names: ("play", "play_v2"),
+        compare: |rng, cases| day23::compare_versions(rng, cases).map(|d| d.to_string()),
+    },
+];
+
+/// Look up the pair of implementations of the given day
+pub fn pair(day: u8) -> Option<&'static Pair> {
+    PAIRS.iter().find(|p| p.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shrinks_disagreements() {
+        // the second sum is off by one from three numbers on
+        let found = compare(
+            &mut Rng::new(0),
+            100,
+            |rng| (0..rng.below(20)).map(|_| rng.below(100)).collect(),
+            |numbers: &Vec<usize>| {
+                let mut simpler = vec![];
+                for i in 0..numbers.len() {
+                    let mut fewer = numbers.clone();
+                    fewer.remove(i);
+                    simpler.push(fewer);
+                    if numbers[i] > 0 {
+                        let mut smaller = numbers.clone();
+                        smaller[i] /= 2;
+                        simpler.push(smaller);
+                    }
+                }
+                simpler
+            },
+            |numbers| numbers.iter().sum::<usize>(),
+            |numbers| numbers.iter().sum::<usize>() + (numbers.len() >= 3) as usize,
+        )
+        .unwrap();
+
+        assert_eq!(found.input, [0, 0, 0]);
+        assert_eq!((found.left, found.right), (0, 1));
+        assert!(found.shrinks > 0);
+        assert!(found
+            .to_string()
+            .starts_with("[0, 0, 0] gives 0 and 1 (case "));
+
+        assert_eq!(
+            compare(
+                &mut Rng::new(0),
+                100,
+                |rng| rng.below(9),
+                |_| vec![],
+                |n| *n,
+                |n| *n
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn versions_agree() {
+        for pair in PAIRS.iter() {
+            assert_eq!(pair.compare(0, 200), None, "day {}", pair.day);
+        }
+        assert_eq!(pair(18).map(|p| p.names.1), Some("Exp::v2"));
+        assert!(pair(1).is_none());
+    }
+}
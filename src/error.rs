@@ -0,0 +1,184 @@
+use std::error::Error;
+use std::fmt;
+
+/// A malformed puzzle input: where the problem is and what it is
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Column number in characters, starting at 1
+    pub column: usize,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, reason: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            reason: reason.into(),
+        }
+    }
+
+    /// An error located at the start of `at`, which must be a slice of `text`
+    pub fn at(text: &str, at: &str, reason: impl Into<String>) -> Self {
+        let offset = (at.as_ptr() as usize)
+            .saturating_sub(text.as_ptr() as usize)
+            .min(text.len());
+        let before = text.get(..offset).unwrap_or(text);
+
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+        Self::new(line, column, reason)
+    }
+
+    /// An error at the end of `text`, for input that stops too early
+    pub fn at_end(text: &str, reason: impl Into<String>) -> Self {
+        Self::at(text, &text[text.len()..], reason)
+    }
+
+    /// Relocate an error located relative to `part`, a slice of `text`, to `text`
+    pub fn within(self, text: &str, part: &str) -> Self {
+        let start = Self::at(text, part, "");
+        Self {
+            line: start.line + self.line - 1,
+            column: if self.line == 1 {
+                start.column + self.column - 1
+            } else {
+                self.column
+            },
+            ..self
+        }
+    }
+
+    /// Move the error down by `lines`, when it was located relative to a part of the input that
+    /// starts on line `lines + 1`
+    pub fn offset_lines(self, lines: usize) -> Self {
+        Self {
+            line: self.line + lines,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.reason
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse the number at the start of `at`, a slice of `text`, reporting its position on error
+pub fn number<T: std::str::FromStr>(text: &str, at: &str) -> Result<T, ParseError> {
+    at.parse()
+        .map_err(|_| ParseError::at(text, at, format!("invalid number {:?}", at)))
+}
+
+/// Check that `at`, a slice of `text`, consists only of the characters in `allowed`
+pub fn only_chars(text: &str, at: &str, allowed: &str) -> Result<(), ParseError> {
+    match at.char_indices().find(|&(_, c)| !allowed.contains(c)) {
+        Some((i, c)) => Err(ParseError::at(
+            text,
+            &at[i..],
+            format!("unexpected character {:?}", c),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Parse every line of `text` with `parse_line`, which locates its errors relative to the line
+pub fn parse_lines<T, F>(text: &str, parse_line: F) -> Result<Vec<T>, ParseError>
+where
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| parse_line(line).map_err(|e| e.offset_lines(i)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_slices() {
+        let text = "first line\nsecond line\n  third";
+
+        let third = text.find("third").unwrap();
+        assert_eq!(
+            ParseError::at(text, &text[third..], "oops"),
+            ParseError::new(3, 3, "oops")
+        );
+
+        let second = text.find("line\n  ").unwrap();
+        assert_eq!(ParseError::at(text, &text[second..], "x").line, 2);
+        assert_eq!(ParseError::at(text, &text[second..], "x").column, 8);
+    }
+
+    #[test]
+    fn locates_end() {
+        assert_eq!(
+            ParseError::at_end("abc\nde", "missing"),
+            ParseError::new(2, 3, "missing")
+        );
+    }
+
+    #[test]
+    fn relocates_errors() {
+        let text = "header\n  a: 1x\n  b: 2\nc";
+        let part = &text[9..];
+        assert_eq!(
+            ParseError::new(1, 4, "x").within(text, part),
+            ParseError::new(2, 6, "x")
+        );
+        assert_eq!(
+            ParseError::new(3, 1, "y").within(text, part),
+            ParseError::new(4, 1, "y")
+        );
+    }
+
+    #[test]
+    fn displays_position() {
+        assert_eq!(
+            ParseError::new(4, 2, "unexpected token").to_string(),
+            "line 4, column 2: unexpected token"
+        );
+    }
+
+    #[test]
+    fn locates_lines() {
+        let parse_line = |line: &str| number::<i32>(line, line.trim());
+        assert_eq!(parse_lines("1\n 2\n3", parse_line), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            parse_lines("1\n  two\n3", parse_line),
+            Err(ParseError::new(2, 3, "invalid number \"two\""))
+        );
+    }
+
+    #[test]
+    fn parses_numbers() {
+        let line = "mem[8] = 11x";
+        assert_eq!(
+            number::<u64>(line, &line[9..]),
+            Err(ParseError::new(1, 10, "invalid number \"11x\""))
+        );
+        assert_eq!(number::<u64>(line, &line[4..5]), Ok(8));
+    }
+
+    #[test]
+    fn checks_chars() {
+        let text = "#..#\n.#x.";
+        assert_eq!(only_chars(text, &text[..4], ".#"), Ok(()));
+        assert_eq!(
+            only_chars(text, &text[5..], ".#"),
+            Err(ParseError::new(2, 3, "unexpected character 'x'"))
+        );
+    }
+}
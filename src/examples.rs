@@ -0,0 +1,236 @@
+//! The example inputs of the puzzles with their expected answers, one file per example
+//!
+//! The examples of day `N` are the files of the `examples/dayNN` directory.  A file starts with
+//! the expected answers, one `partN = <answer>` line per part, then a `---` line and the input:
+//!
+//! ```text
+//! part1 = 5
+//! part2 = "mxmxvkd,sqjhc,fvjkl"
+//! ---
+//! mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
+//! ```
+//!
+//! Parts without an expected answer are not solved, some examples only apply to one part.  A
+//! `slow = <parts>` line lists the parts, separated by commas, which take too long to solve in
+//! a debug build.
+
+use crate::answers::{is_blank, part_answer, Answers, Verdict};
+use crate::error::number;
+use crate::{puzzle, Answer, ParseError};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A part with its answer and how the answer compares with the expected one
+pub type Outcome = (u8, Answer, Verdict);
+
+/// An example input of a puzzle
+#[derive(Clone, Debug, PartialEq)]
+pub struct Example {
+    pub day: u8,
+    /// The file the example was read from
+    pub path: PathBuf,
+    pub input: String,
+    pub expected: Answers,
+    /// The parts which take long to solve
+    pub slow: Vec<u8>,
+}
+
+impl Example {
+    pub fn parse(day: u8, text: &str) -> Result<Self, ParseError> {
+        let mut expected = Answers::new();
+        let mut slow = vec![];
+        let mut rest = text;
+
+        loop {
+            let (line, next) = match rest.split_once('\n') {
+                Some((line, next)) => (line, next),
+                None if !rest.is_empty() => (rest, ""),
+                None => return Err(ParseError::at_end(text, "expected \"---\"")),
+            };
+            rest = next;
+
+            let line = line.trim();
+            if line == "---" {
+                break;
+            } else if is_blank(line) {
+                continue;
+            } else if let Some(parts) = line.strip_prefix("slow") {
+                let parts = parts
+                    .trim_start()
+                    .strip_prefix('=')
+                    .ok_or_else(|| ParseError::at(text, line, "expected \"slow = <parts>\""))?;
+                for part in parts.split(',') {
+                    slow.push(number(text, part.trim())?);
+                }
+            } else {
+                let (part, answer) = part_answer(text, line)?;
+                expected.insert(day, part, answer);
+            }
+        }
+
+        Ok(Self {
+            day,
+            path: PathBuf::new(),
+            input: rest.to_string(),
+            expected,
+            slow,
+        })
+    }
+
+    pub fn read(day: u8, path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let example = Self::parse(day, &text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            ..example
+        })
+    }
+
+    /// The parts with an expected answer, the slow ones only if `slow` is set
+    pub fn parts(&self, slow: bool) -> Vec<u8> {
+        (1..=2)
+            .filter(|part| self.expected.get(self.day, *part).is_some())
+            .filter(|part| slow || !self.slow.contains(part))
+            .collect()
+    }
+
+    /// Solve the given parts and compare the answers with the expected ones
+    pub fn check(&self, parts: &[u8]) -> Result<Vec<Outcome>, Box<dyn Error>> {
+        let puzzle = puzzle(self.day).ok_or_else(|| format!("No puzzle on day {}", self.day))?;
+        let answers = puzzle.solve(&self.input, parts)?;
+
+        Ok(parts
+            .iter()
+            .zip(answers)
+            .map(|(&part, answer)| {
+                let verdict = self.expected.check(self.day, part, &answer);
+                (part, answer, verdict)
+            })
+            .collect())
+    }
+}
+
+/// The examples of every day under `dir`, by day then by file name
+pub fn load(dir: &Path) -> Result<Vec<Example>, Box<dyn Error>> {
+    let mut examples = vec![];
+
+    for day in 1..=25 {
+        let day_dir = dir.join(format!("day{:02}", day));
+        if !day_dir.is_dir() {
+            continue;
+        }
+
+        let mut paths = fs::read_dir(&day_dir)
+            .map_err(|e| format!("{}: {}", day_dir.display(), e))?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.sort();
+
+        for path in paths.iter().filter(|p| p.is_file()) {
+            examples.push(Example::read(day, path)?);
+        }
+    }
+
+    Ok(examples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+# from the puzzle description
+part1 = 5
+part2 = \"mxmxvkd,sqjhc,fvjkl\"
+slow = 2
+---
+mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
+";
+
+    /// Solve the examples of every day, the slow parts only if `slow` is set
+    fn check_examples(slow: bool) {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples");
+        let examples = load(&dir).unwrap();
+        assert!(!examples.is_empty());
+
+        let mut failures = vec![];
+        for example in &examples {
+            let parts: Vec<u8> = example
+                .parts(slow)
+                .into_iter()
+                .filter(|part| slow == example.slow.contains(part))
+                .collect();
+            if parts.is_empty() {
+                continue;
+            }
+
+            match example.check(&parts) {
+                Ok(results) => {
+                    for (part, answer, verdict) in results {
+                        if let Verdict::Fail(expected) = verdict {
+                            failures.push(format!(
+                                "{} part {}: expected {}, found {}",
+                                example.path.display(),
+                                part,
+                                expected,
+                                answer
+                            ));
+                        }
+                    }
+                }
+                Err(e) => failures.push(format!("{}: {}", example.path.display(), e)),
+            }
+        }
+
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn parses_examples() {
+        let example = Example::parse(21, EXAMPLE).unwrap();
+        assert_eq!(example.expected.get(21, 1), Some(&Answer::Integer(5)));
+        assert_eq!(
+            example.expected.get(21, 2),
+            Some(&Answer::Text("mxmxvkd,sqjhc,fvjkl".to_string()))
+        );
+        assert_eq!(example.slow, [2]);
+        assert_eq!(example.parts(false), [1]);
+        assert_eq!(example.parts(true), [1, 2]);
+        assert_eq!(
+            example.input,
+            "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)\n"
+        );
+
+        let example = Example::parse(1, "part2 = 3\n---\n").unwrap();
+        assert_eq!(example.parts(true), [2]);
+        assert_eq!(example.input, "");
+    }
+
+    #[test]
+    fn rejects_malformed_examples() {
+        assert_eq!(
+            Example::parse(1, "part1 = 1\n"),
+            Err(ParseError::new(2, 1, "expected \"---\""))
+        );
+        assert_eq!(
+            Example::parse(1, "part1 = x\n---\n"),
+            Err(ParseError::new(1, 9, "invalid number \"x\""))
+        );
+        assert_eq!(
+            Example::parse(1, "slow 2\n---\n"),
+            Err(ParseError::new(1, 1, "expected \"slow = <parts>\""))
+        );
+    }
+
+    #[test]
+    fn solves_examples() {
+        check_examples(false);
+    }
+
+    #[test]
+    #[ignore]
+    fn solves_slow_examples() {
+        check_examples(true);
+    }
+}
@@ -0,0 +1,287 @@
+//! Download the puzzle inputs into a local cache
+//!
+//! The input of day `N` is served at `<base URL>/day/N/input` to the user logged in with the
+//! session cookie.  A downloaded input is stored as `dayNN.txt` in the cache directory and never
+//! downloaded again.  Plain `http://` URLs, such as a local stub, are fetched directly,
+//! `https://` ones with `curl`, which reads the cookie from its standard input.
+
+use crate::input::{day_path, input_dir};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Environment variable holding the session cookie
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Environment variable overriding the base URL of the puzzles
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+/// Base URL used when the environment variable is not set
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2020";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fetcher {
+    base_url: String,
+    session: Option<String>,
+    cache: PathBuf,
+}
+
+impl Fetcher {
+    pub fn new(base_url: &str, session: Option<&str>, cache: PathBuf) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.map(String::from),
+            cache,
+        }
+    }
+
+    /// A fetcher configured by `$AOC_BASE_URL` and `$AOC_SESSION`, caching in the input
+    /// directory
+    pub fn from_env() -> Self {
+        let base_url = env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let session = env::var(SESSION_VAR).ok().filter(|s| !s.is_empty());
+        Self::new(&base_url, session.as_deref(), input_dir())
+    }
+
+    pub fn with_base_url(self, base_url: &str) -> Self {
+        Self::new(base_url, self.session.as_deref(), self.cache)
+    }
+
+    pub fn with_session(self, session: &str) -> Self {
+        Self::new(&self.base_url, Some(session), self.cache)
+    }
+
+    /// Whether a session token is set, without which nothing can be downloaded
+    pub fn has_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/day/{}/input", self.base_url, day)
+    }
+
+    /// The cached input file of the given day
+    pub fn path(&self, day: u8) -> PathBuf {
+        day_path(&self.cache, day)
+    }
+
+    pub fn is_cached(&self, day: u8) -> bool {
+        self.path(day).is_file()
+    }
+
+    /// The cached input file of the given day, downloaded first if it isn't cached yet
+    pub fn fetch(&self, day: u8) -> Result<PathBuf, Box<dyn Error>> {
+        let path = self.path(day);
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        let session = self.session.as_ref().ok_or_else(|| {
+            format!(
+                "No session token to download day {}, set {}",
+                day, SESSION_VAR
+            )
+        })?;
+        let url = self.url(day);
+        let input = get(&url, session).map_err(|e| format!("{}: {}", url, e))?;
+
+        // write to a temporary file first, so an interrupted download leaves no partial input
+        let partial = path.with_extension("txt.part");
+        fs::create_dir_all(&self.cache)
+            .and_then(|_| fs::write(&partial, input))
+            .and_then(|_| fs::rename(&partial, &path))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        Ok(path)
+    }
+}
+
+/// The body of the page at `url`, requested with the session cookie
+fn get(url: &str, session: &str) -> Result<String, Box<dyn Error>> {
+    if let Some(rest) = url.strip_prefix("http://") {
+        http_get(rest, session)
+    } else if url.starts_with("https://") {
+        curl(url, session)
+    } else {
+        Err("Expected an http:// or https:// URL".into())
+    }
+}
+
+/// A plain HTTP/1.0 request, so the response is neither chunked nor kept alive.  `url` is
+/// the URL without its scheme.
+fn http_get(url: &str, session: &str) -> Result<String, Box<dyn Error>> {
+    let (host, path) = match url.find('/') {
+        Some(i) => (&url[..i], &url[i..]),
+        None => (url, "/"),
+    };
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+
+    let mut stream = TcpStream::connect(address)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: {}\r\nCookie: session={}\r\nUser-Agent: aoc2020\r\n\r\n",
+        path, host, session
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("Malformed HTTP response")?;
+    let status = head.lines().next().unwrap_or_default();
+    match status.split_whitespace().nth(1) {
+        Some("200") => Ok(body.to_string()),
+        _ => Err(status.into()),
+    }
+}
+
+/// The session cookie as a curl config line, read from the standard input so that the token
+/// doesn't show in the command line of the process
+fn curl_config(session: &str) -> String {
+    let escaped = session.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("cookie = \"session={}\"\n", escaped)
+}
+
+fn curl(url: &str, session: &str) -> Result<String, Box<dyn Error>> {
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--user-agent",
+            "aoc2020",
+            "--config",
+            "-",
+        ])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("curl: {}", e))?;
+
+    // dropped at the end of the statement, closing the standard input of curl
+    child
+        .stdin
+        .take()
+        .ok_or("curl: no standard input")?
+        .write_all(curl_config(session).as_bytes())?;
+    let output = child.wait_with_output()?;
+
+    if output.status.success() {
+        Ok(String::from_utf8(output.stdout)?)
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// A server answering one request with `response`, returning its base URL and the request
+    fn stub(response: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/2020", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut buf = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (base_url, server)
+    }
+
+    fn cache(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc2020-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn downloads_once() {
+        let (base_url, server) = stub("HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n1\n2\n3\n");
+        let cache = cache("downloads");
+        let fetcher = Fetcher::new(&base_url, Some("abc"), cache.clone());
+        assert!(!fetcher.is_cached(7));
+
+        let path = fetcher.fetch(7).unwrap();
+        assert_eq!(path, cache.join("day07.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2020/day/7/input HTTP/1.0\r\n"));
+        assert!(request.contains("\r\nCookie: session=abc\r\n"));
+
+        // the stub is gone, the input comes from the cache
+        assert!(fetcher.is_cached(7));
+        assert_eq!(fetcher.fetch(7).unwrap(), path);
+        fs::remove_dir_all(cache).unwrap();
+    }
+
+    #[test]
+    fn reports_failures() {
+        let (base_url, server) = stub("HTTP/1.1 404 Not Found\r\n\r\nPlease log in");
+        let cache = cache("failures");
+        let fetcher = Fetcher::new(&base_url, Some("abc"), cache.clone());
+
+        let err = fetcher.fetch(26).unwrap_err().to_string();
+        server.join().unwrap();
+        assert!(err.ends_with("/2020/day/26/input: HTTP/1.1 404 Not Found"));
+        assert!(!fetcher.is_cached(26));
+
+        let err = Fetcher::new(&base_url, None, cache).fetch(1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No session token to download day 1, set AOC_SESSION"
+        );
+    }
+
+    #[test]
+    fn writes_the_curl_config() {
+        assert_eq!(curl_config("abc"), "cookie = \"session=abc\"\n");
+        assert_eq!(curl_config("a\"b\\"), "cookie = \"session=a\\\"b\\\\\"\n");
+    }
+
+    #[test]
+    fn passes_the_session_to_curl() {
+        // curl is only needed for https://, builds without it skip this
+        if Command::new("curl").arg("--version").output().is_err() {
+            return;
+        }
+
+        let (base_url, server) = stub("HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n1\n");
+        let url = format!("{}/day/1/input", base_url);
+        assert_eq!(curl(&url, "abc").unwrap(), "1\n");
+        assert!(server
+            .join()
+            .unwrap()
+            .contains("\r\nCookie: session=abc\r\n"));
+    }
+
+    #[test]
+    fn builds_urls() {
+        let fetcher = Fetcher::new("https://adventofcode.com/2020/", None, PathBuf::new());
+        assert_eq!(fetcher.url(3), "https://adventofcode.com/2020/day/3/input");
+        assert_eq!(
+            fetcher.with_base_url("http://localhost:8080").url(25),
+            "http://localhost:8080/day/25/input"
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! A rectangular grid of cells, as drawn in many puzzle inputs
+//!
+//! Cells are addressed by `(col, row)` from the top left corner.  Indexing with `usize`
+//! coordinates panics outside the grid, `get` takes signed coordinates and returns `None`
+//! instead, and `wrapping` repeats the grid in every direction.
+
+use crate::error::only_chars;
+use crate::ParseError;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// The offsets of the eight neighbours of a cell
+pub const NEIGHBOURS: [(isize, isize); 8] = [
+    (0, -1),
+    (0, 1),
+    (-1, 0),
+    (1, 0),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// A grid of `width` columns from the cells in row-major order
+    pub fn new(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "{} cells don't fill rows of {}",
+            cells.len(),
+            width
+        );
+        let height = cells.len() / width;
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// A grid with the cells computed from their `(col, row)` position
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (col, row)))
+            .map(|(col, row)| f(col, row))
+            .collect();
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at `(col, row)`, `None` outside the grid
+    pub fn get(&self, col: isize, row: isize) -> Option<&T> {
+        if 0 <= col && (col as usize) < self.width && 0 <= row && (row as usize) < self.height {
+            Some(&self[(col as usize, row as usize)])
+        } else {
+            None
+        }
+    }
+
+    /// The cell at `(col, row)` of the grid repeated in every direction
+    pub fn wrapping(&self, col: isize, row: isize) -> &T {
+        let col = col.rem_euclid(self.width as isize) as usize;
+        let row = row.rem_euclid(self.height as isize) as usize;
+        &self[(col, row)]
+    }
+
+    /// The cells in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// The `(col, row)` positions in row-major order
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (col, row)))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.width, "column {} out of {}", col, self.width);
+        self.cells.iter().skip(col).step_by(self.width)
+    }
+
+    /// The neighbours of `(col, row)` inside the grid
+    pub fn neighbours(&self, col: usize, row: usize) -> impl Iterator<Item = &T> {
+        NEIGHBOURS
+            .iter()
+            .filter_map(move |&(dc, dr)| self.get(col as isize + dc, row as isize + dr))
+    }
+
+    /// The cells from `(col, row)` in the direction `(dc, dr)` to the edge, excluding the start
+    pub fn ray(
+        &self,
+        col: usize,
+        row: usize,
+        (dc, dr): (isize, isize),
+    ) -> impl Iterator<Item = &T> {
+        (1..).map_while(move |i| self.get(col as isize + i * dc, row as isize + i * dr))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Rotate the grid 90 degrees counter-clockwise
+    pub fn rotate_left(&self) -> Self {
+        Self::from_fn(self.height, self.width, |col, row| {
+            self[(self.width - 1 - row, col)].clone()
+        })
+    }
+
+    /// Mirror the grid left to right
+    pub fn flip(&self) -> Self {
+        Self::from_fn(self.width, self.height, |col, row| {
+            self[(self.width - 1 - col, row)].clone()
+        })
+    }
+
+    /// The `width` by `height` part of the grid starting at `(col, row)`
+    pub fn crop(&self, col: usize, row: usize, width: usize, height: usize) -> Self {
+        Self::from_fn(width, height, |c, r| self[(col + c, row + r)].clone())
+    }
+}
+
+impl Grid<char> {
+    /// Parse the lines of `text` made of the `allowed` characters.  The lines are trimmed and
+    /// surrounding blank lines are ignored.
+    pub fn parse(text: &str, allowed: &str) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+
+        for line in text.trim().lines().map(|line| line.trim()) {
+            only_chars(text, line, allowed)?;
+            if *width.get_or_insert(line.len()) != line.len() {
+                return Err(ParseError::at(text, line, "rows of different length"));
+            }
+            cells.extend(line.chars());
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self::new(width, cells)),
+            _ => Err(ParseError::at_end(text, "empty grid")),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (col, row): (usize, usize)) -> &T {
+        assert!(
+            col < self.width && row < self.height,
+            "({}, {}) outside the grid",
+            col,
+            row
+        );
+        &self.cells[col + row * self.width]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (col, row): (usize, usize)) -> &mut T {
+        assert!(
+            col < self.width && row < self.height,
+            "({}, {}) outside the grid",
+            col,
+            row
+        );
+        &mut self.cells[col + row * self.width]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse("\n  ab.\n  #cd\n", "abcd.#").unwrap()
+    }
+
+    #[test]
+    fn parses_grids() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(0, 1)], '#');
+        assert_eq!(grid.to_string(), "ab.\n#cd\n");
+
+        assert_eq!(
+            Grid::parse("..\n.", ".#"),
+            Err(ParseError::new(2, 1, "rows of different length"))
+        );
+        assert_eq!(
+            Grid::parse("..\n.x", ".#"),
+            Err(ParseError::new(2, 2, "unexpected character 'x'"))
+        );
+        assert_eq!(
+            Grid::parse("\n", ".#"),
+            Err(ParseError::new(2, 1, "empty grid"))
+        );
+    }
+
+    #[test]
+    fn accesses_cells() {
+        let grid = example();
+        assert_eq!(grid.get(2, 1), Some(&'d'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, -1), None);
+        assert_eq!(grid.wrapping(3, 0), &'a');
+        assert_eq!(grid.wrapping(-1, -1), &'d');
+        assert_eq!(grid.row(1), ['#', 'c', 'd']);
+        assert_eq!(grid.column(1).collect::<String>(), "bc");
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.positions().nth(4), Some((1, 1)));
+    }
+
+    #[test]
+    fn finds_neighbours() {
+        let grid = example();
+        assert_eq!(grid.neighbours(0, 0).collect::<String>(), "#bc");
+        assert_eq!(grid.neighbours(1, 1).count(), 5);
+        assert_eq!(grid.ray(0, 0, (1, 0)).collect::<String>(), "b.");
+        assert_eq!(grid.ray(0, 0, (0, -1)).count(), 0);
+    }
+
+    #[test]
+    fn transforms_grids() {
+        let grid = example();
+        assert_eq!(grid.rotate_left().to_string(), ".d\nbc\na#\n");
+        assert_eq!(grid.flip().to_string(), ".ba\ndc#\n");
+        assert_eq!(grid.crop(1, 0, 2, 2).to_string(), "b.\ncd\n");
+        assert_eq!(grid.map(|&c| c == '#').iter().filter(|&&b| b).count(), 1);
+        assert_eq!(
+            grid.rotate_left().rotate_left().rotate_left().rotate_left(),
+            grid
+        );
+        assert_eq!(Grid::from_fn(2, 1, |col, _| col), Grid::new(2, vec![0, 1]));
+    }
+}
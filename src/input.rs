@@ -0,0 +1,190 @@
+use std::env;
+use std::fmt;
+use std::fs::read_to_string;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the directory of the puzzle inputs
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// Directory used when the environment variable is not set
+pub const DEFAULT_INPUT_DIR: &str = "inputs";
+
+/// Where to read a puzzle input from
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// The source named on the command line: a path, or `-` for the standard input
+    pub fn new(arg: &str) -> Self {
+        match arg {
+            "-" => Source::Stdin,
+            path => Source::File(PathBuf::from(path)),
+        }
+    }
+
+    /// The input file of the given day in the input directory
+    pub fn day(day: u8) -> Self {
+        Source::File(day_path(&input_dir(), day))
+    }
+
+    /// The text of the input, normalised
+    pub fn read(&self) -> io::Result<String> {
+        let text = match self {
+            Source::File(path) => read_to_string(path)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?,
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                input
+            }
+        };
+        Ok(normalize(&text))
+    }
+}
+
+/// The path of the file, or `-` for the standard input
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "-"),
+        }
+    }
+}
+
+/// The text with `\n` line endings, without a byte order mark, trailing whitespace or
+/// trailing blank lines, and ending in a newline unless it is empty.  Lines keep their number
+/// and characters their column.
+pub fn normalize(text: &str) -> String {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut normalized = String::with_capacity(text.len() + 1);
+    for line in text.trim_end().lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// The paragraphs of the text, runs of lines separated by blank lines.  Every paragraph is a
+/// slice of the text from the start of its first line to the end of its last one, so parse
+/// errors can be located in the text.
+pub fn paragraphs(text: &str) -> impl Iterator<Item = &str> {
+    // the line at the start of `text` without its line ending, and the text after it
+    fn split_line(text: &str) -> (&str, &str) {
+        match text.split_once('\n') {
+            Some((line, rest)) => (line.strip_suffix('\r').unwrap_or(line), rest),
+            None => (text, ""),
+        }
+    }
+
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        loop {
+            let (line, next) = split_line(rest);
+            if !line.trim().is_empty() {
+                break;
+            } else if next.is_empty() {
+                return None;
+            }
+            rest = next;
+        }
+
+        let start = rest;
+        let mut len = 0;
+        while !rest.is_empty() {
+            let (line, next) = split_line(rest);
+            if line.trim().is_empty() {
+                break;
+            }
+            len = line.as_ptr() as usize - start.as_ptr() as usize + line.len();
+            rest = next;
+        }
+
+        Some(&start[..len])
+    })
+}
+
+/// The directory of the puzzle inputs, `$AOC_INPUT_DIR` or `inputs`
+pub fn input_dir() -> PathBuf {
+    env::var_os(INPUT_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUT_DIR))
+}
+
+/// The input file of the given day in `dir`
+pub fn day_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{:02}.txt", day))
+}
+
+/// Read the input of the given day from the source named by the first command line argument,
+/// or from the input directory if there is none.
+pub fn from_args(day: u8) -> io::Result<String> {
+    match env::args().nth(1) {
+        Some(arg) => Source::new(&arg).read(),
+        None => Source::day(day).read(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_source() {
+        assert_eq!(Source::new("-"), Source::Stdin);
+        assert_eq!(
+            Source::new("other/day07.txt"),
+            Source::File(PathBuf::from("other/day07.txt"))
+        );
+    }
+
+    #[test]
+    fn displays_source() {
+        assert_eq!(Source::new("-").to_string(), "-");
+        assert_eq!(
+            Source::new("other/day07.txt").to_string(),
+            "other/day07.txt"
+        );
+    }
+
+    #[test]
+    fn names_day_files() {
+        assert_eq!(
+            day_path(Path::new("accounts/alice"), 7),
+            PathBuf::from("accounts/alice/day07.txt")
+        );
+        assert_eq!(
+            day_path(Path::new("inputs"), 25),
+            PathBuf::from("inputs/day25.txt")
+        );
+    }
+
+    #[test]
+    fn normalizes_text() {
+        assert_eq!(normalize("\u{feff}a \r\n\t\r\nb"), "a\n\nb\n");
+        assert_eq!(normalize("a\n\n \n"), "a\n");
+        assert_eq!(normalize(" \r\n"), "");
+        assert_eq!(normalize("  a\n"), "  a\n");
+    }
+
+    #[test]
+    fn splits_paragraphs() {
+        let text = "\r\na\r\nb \r\n \r\n\r\nc\n\n";
+        assert_eq!(paragraphs(text).collect::<Vec<_>>(), ["a\r\nb ", "c"]);
+        assert_eq!(paragraphs("a\n\t\nb").collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(paragraphs("a").collect::<Vec<_>>(), ["a"]);
+        assert_eq!(paragraphs(" \n\n").count(), 0);
+        assert_eq!(paragraphs("").count(), 0);
+    }
+
+    #[test]
+    fn reports_missing_file() {
+        let err = Source::new("no/such/day99.txt").read().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().starts_with("no/such/day99.txt: "));
+    }
+}
@@ -0,0 +1,49 @@
+//! Just enough JSON to report results to scripts
+
+use crate::Answer;
+use std::fmt::Write;
+
+/// A JSON string literal
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A number for integer answers, a string otherwise
+pub fn answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Integer(n) => n.to_string(),
+        Answer::Text(s) => string(s),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(string("mxmxvkd,sqjhc"), r#""mxmxvkd,sqjhc""#);
+        assert_eq!(string("a \"b\"\\\n"), r#""a \"b\"\\\n""#);
+        assert_eq!(string("\u{1}"), r#""\u0001""#);
+    }
+
+    #[test]
+    fn formats_answers() {
+        assert_eq!(answer(&Answer::from(306)), "306");
+        assert_eq!(answer(&Answer::from("67384529")), r#""67384529""#);
+    }
+}
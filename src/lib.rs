@@ -0,0 +1,209 @@
+//! Solutions to the programming puzzles in the Advent of Code 2020.
+
+use fetch::Fetcher;
+use input::Source;
+use observer::{Observer, Silent};
+use random::Rng;
+use repl::Explorer;
+use std::error::Error;
+use std::io;
+use std::time::{Duration, Instant};
+
+pub mod answers;
+pub mod automaton;
+pub mod bench;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod differential;
+pub mod error;
+pub mod examples;
+pub mod fetch;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod modular;
+pub mod observer;
+pub mod random;
+pub mod repl;
+pub mod solution;
+
+pub use error::ParseError;
+pub use solution::{Answer, Solution};
+
+/// Parse an input and solve the given parts of a puzzle
+type Solver = fn(&str, &[u8], &mut dyn Observer) -> Result<Timed, Box<dyn Error>>;
+
+/// The answers of a puzzle with the time taken to parse the input and to solve each part
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timed {
+    pub parse: Duration,
+    pub parts: Vec<(Answer, Duration)>,
+}
+
+/// A puzzle of the calendar together with its solution.
+pub struct Puzzle {
+    pub day: u8,
+    pub title: &'static str,
+    /// Number of parts of the puzzle
+    pub parts: u8,
+    /// The usage and description of the queries of the shell, see `repl`
+    pub queries: &'static [(&'static str, &'static str)],
+    solve: Solver,
+    generate: fn(&mut Rng, usize) -> String,
+    explore: fn(&str) -> Result<Box<dyn Explorer>, ParseError>,
+}
+
+fn solve<S: Solution>(
+    input: &str,
+    parts: &[u8],
+    observer: &mut dyn Observer,
+) -> Result<Timed, Box<dyn Error>> {
+    let start = Instant::now();
+    let input = S::parse(&input::normalize(input))?;
+    let parse = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            observer.part(part);
+            let start = Instant::now();
+            let answer = S::solve_with(&input, part, observer)
+                .ok_or_else(|| format!("No part {}", part))?
+                .map_err(|e| format!("Part {}: {}", part, e))?;
+            let elapsed = start.elapsed();
+            observer.done();
+            Ok((answer, elapsed))
+        })
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    Ok(Timed { parse, parts })
+}
+
+impl Puzzle {
+    /// Read the puzzle input from the given source, or from the input directory if there is none.
+    /// A missing input file is downloaded first if a session token is set, see `fetch`.
+    pub fn read_input(&self, source: Option<&Source>) -> io::Result<String> {
+        if source.is_none() {
+            let fetcher = Fetcher::from_env();
+            if fetcher.has_session() && !fetcher.is_cached(self.day) {
+                fetcher
+                    .fetch(self.day)
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+            }
+        }
+        self.source(source).read()
+    }
+
+    /// The given source, or the input file in the input directory if there is none
+    pub fn source(&self, source: Option<&Source>) -> Source {
+        source.cloned().unwrap_or_else(|| Source::day(self.day))
+    }
+
+    /// The parts this puzzle can solve.  Day 25 has only one.
+    pub fn parts(&self) -> Vec<u8> {
+        (1..=self.parts).collect()
+    }
+
+    /// Parse the puzzle input once and solve the given parts.  The input is normalised first,
+    /// see `input::normalize`.  Fails on malformed input or if a part does not exist.
+    pub fn solve(&self, input: &str, parts: &[u8]) -> Result<Vec<Answer>, Box<dyn Error>> {
+        let timed = self.timed(input, parts)?;
+        Ok(timed.parts.into_iter().map(|(answer, _)| answer).collect())
+    }
+
+    /// Like `solve`, also measuring the time taken by the parser and by each part
+    pub fn timed(&self, input: &str, parts: &[u8]) -> Result<Timed, Box<dyn Error>> {
+        self.timed_with(input, parts, &mut Silent)
+    }
+
+    /// Like `timed`, reporting the progress of iterative solvers to `observer`
+    pub fn timed_with(
+        &self,
+        input: &str,
+        parts: &[u8],
+        observer: &mut dyn Observer,
+    ) -> Result<Timed, Box<dyn Error>> {
+        (self.solve)(input, parts, observer)
+    }
+
+    /// A random valid input from `seed`, the same for the same seed and size
+    pub fn generate(&self, seed: u64, size: usize) -> String {
+        (self.generate)(&mut Rng::new(seed), size)
+    }
+
+    /// Parse the puzzle input to explore it in the shell
+    pub fn explore(&self, input: &str) -> Result<Box<dyn Explorer>, ParseError> {
+        (self.explore)(input)
+    }
+}
+
+macro_rules! puzzle {
+    ($day:literal, $title:literal, $module:ident::$solution:ident) => {
+        Puzzle {
+            day: $day,
+            title: $title,
+            parts: <$module::$solution as Solution>::PARTS,
+            queries: <$module::$solution as Solution>::QUERIES,
+            solve: solve::<$module::$solution>,
+            generate: <$module::$solution as Solution>::generate,
+            explore: repl::explore::<$module::$solution>,
+        }
+    };
+}
+
+/// All the puzzles in calendar order.
+pub const PUZZLES: [Puzzle; 25] = [
+    puzzle!(1, "Report Repair", day01::Day01),
+    puzzle!(2, "Password Philosophy", day02::Day02),
+    puzzle!(3, "Toboggan Trajectory", day03::Day03),
+    puzzle!(4, "Passport Processing", day04::Day04),
+    puzzle!(5, "Binary Boarding", day05::Day05),
+    puzzle!(6, "Custom Customs", day06::Day06),
+    puzzle!(7, "Handy Haversacks", day07::Day07),
+    puzzle!(8, "Handheld Halting", day08::Day08),
+    puzzle!(9, "Encoding Error", day09::Day09),
+    puzzle!(10, "Adapter Array", day10::Day10),
+    puzzle!(11, "Seating System", day11::Day11),
+    puzzle!(12, "Rain Risk", day12::Day12),
+    puzzle!(13, "Shuttle Search", day13::Day13),
+    puzzle!(14, "Docking Data", day14::Day14),
+    puzzle!(15, "Rambunctious Recitation", day15::Day15),
+    puzzle!(16, "Ticket Translation", day16::Day16),
+    puzzle!(17, "Conway Cubes", day17::Day17),
+    puzzle!(18, "Operation Order", day18::Day18),
+    puzzle!(19, "Monster Messages", day19::Day19),
+    puzzle!(20, "Jurassic Jigsaw", day20::Day20),
+    puzzle!(21, "Allergen Assessment", day21::Day21),
+    puzzle!(22, "Crab Combat", day22::Day22),
+    puzzle!(23, "Crab Cups", day23::Day23),
+    puzzle!(24, "Lobby Layout", day24::Day24),
+    puzzle!(25, "Combo Breaker", day25::Day25),
+];
+
+/// Look up the puzzle of the given day.
+pub fn puzzle(day: u8) -> Option<&'static Puzzle> {
+    PUZZLES.iter().find(|p| p.day == day)
+}
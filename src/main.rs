@@ -1,3 +1,637 @@
+use aoc2020::answers::{Answers, Verdict};
+use aoc2020::bench;
+use aoc2020::differential;
+use aoc2020::fetch::Fetcher;
+use aoc2020::input::{self, Source};
+use aoc2020::observer::{step_range, Observer, Progress, Silent, Trace};
+use aoc2020::repl::Repl;
+use aoc2020::{day02, day03, json, puzzle, Puzzle, PUZZLES};
+use std::env;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
+
+const USAGE: &str = "\
+Usage: aoc2020 <command>
+
+Commands:
+    list                     List the available puzzles
+    run <day> [--part <n>] [--input <file>] [--json] [--progress|--trace]
+        [--trace-file <file>] [--steps <from>..<to>]
+                             Solve one day, optionally only one part.  The
+                             input file defaults to dayNN.txt in the input
+                             directory, `-` reads the standard input.
+                             The iterative solvers (days 11, 17, 22, 23 and
+                             24) show their progress on the standard error
+                             with --progress, or every step and its state
+                             with --trace.  Days 11, 22 and 23 trace what
+                             happens in each step instead: the seats taken
+                             and left, the rounds of Combat, the moves of
+                             the crab.  --trace-file writes the trace to a
+                             file, --steps traces only the given steps.
+    all [--json]             Solve all days in sequence
+    verify <file>            Solve all days and check the answers against the
+                             expected ones in <file>, plain text or TOML
+    record <file>            Solve all days and record the answers in <file>,
+                             TOML if it ends with .toml, plain text otherwise
+    bench [<day>...] [--runs <n>] [--json]
+                             Time parsing and each part over <n> runs
+                             (default 10) and print min/median/max as a
+                             table, or as JSON.  Benchmarks all days if none
+                             is given.
+    generate <day> [--seed <n>] [--size <n>]
+                             Print a random valid input for the day, the
+                             same for the same seed (default 0).  The size
+                             (default 10) scales the input: the number of
+                             lines, tiles per side, cards per player, ...
+    compare <day> [--seed <n>] [--cases <n>]
+                             Run the two implementations of a day (15, 18
+                             and 23) on <n> random inputs (default 1000)
+                             and print the first one they disagree on,
+                             shrunk to a minimal case
+    fetch [<day>...] [--session <token>] [--base-url <url>]
+                             Download the inputs of the given days, or of
+                             all days, into the input directory.  Inputs
+                             already there are never downloaded again.
+    audit [--policy <policy>]... [--input <file>] [--report [--csv]]
+                             Count the passwords of day 2 valid by each
+                             policy, those of both parts by default.  The
+                             policies: count and xor (the two parts), and
+                             (the letter at both positions),
+                             forbidden:<substring>,... and classes:<n> (at
+                             least <n> of lowercase, uppercase, digits and
+                             symbols).  --report prints every line with the
+                             verdict of every policy and why it failed,
+                             lines which are not entries included, --csv
+                             prints it as CSV.
+    toboggan [<right>,<down>]... [--search <n>] [--draw <right>,<down>]
+        [--input <file>]
+                             Count the trees on the map of day 3 down each
+                             slope, those of part 2 by default, and their
+                             product.  A negative right moves left.
+                             --search tries every slope up to <n> squares
+                             right or left and down, and prints those with
+                             the fewest and the most trees.  --draw prints
+                             the map with the path down the slope: O on
+                             the trees hit and X on open squares.
+    repl [<day>] [--input <file>]
+                             Explore the parsed input of a day
+                             interactively: solve it, show the state of a
+                             simulation after any step and query the parsed
+                             structures, the bags of day 7, the rules of day
+                             19, the tiles of day 20, ...  Type help for the
+                             commands.
+
+With --json every answer is printed as one JSON object per line with the day,
+part, answer, time taken to solve the part in nanoseconds and the input path.
+
+Environment:
+    AOC_INPUT_DIR            Directory of the puzzle inputs (default: inputs)
+    AOC_SESSION              Session cookie to download the inputs with.  When
+                             set, a missing input file is downloaded first.
+    AOC_BASE_URL             URL of the puzzles
+                             (default: https://adventofcode.com/2020)
+";
+
+/// How to print the answers
+#[derive(Clone, Copy, PartialEq)]
+enum Output {
+    Text,
+    Json,
+}
+
+fn solve(
+    puzzle: &Puzzle,
+    parts: &[u8],
+    source: Option<&Source>,
+    output: Output,
+    observer: &mut dyn Observer,
+) -> Result<(), Box<dyn Error>> {
+    let input = puzzle.read_input(source)?;
+    let source = puzzle.source(source);
+
+    let timed = puzzle
+        .timed_with(&input, parts, observer)
+        .map_err(|e| format!("Day {}: {}", puzzle.day, e))?;
+
+    if output == Output::Text {
+        println!("Day {:02}: {}", puzzle.day, puzzle.title);
+    }
+    for (part, (answer, elapsed)) in parts.iter().zip(timed.parts) {
+        match output {
+            Output::Text => println!("Part {} {}", part, answer),
+            Output::Json => println!(
+                r#"{{"day":{},"part":{},"answer":{},"elapsed_ns":{},"input":{}}}"#,
+                puzzle.day,
+                part,
+                json::answer(&answer),
+                elapsed.as_nanos(),
+                json::string(&source.to_string())
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+fn list() {
+    for puzzle in PUZZLES.iter() {
+        let parts = puzzle.parts();
+        println!(
+            "Day {:02}: {} ({} part{})",
+            puzzle.day,
+            puzzle.title,
+            parts.len(),
+            if parts.len() == 1 { "" } else { "s" }
+        );
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut day = None;
+    let mut part = None;
+    let mut source = None;
+    let mut output = Output::Text;
+    let mut progress = false;
+    let mut trace = false;
+    let mut trace_file = None;
+    let mut steps = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => {
+                let value = args.next().ok_or("Missing value for --part")?;
+                part = Some(value.parse().map_err(|_| "Invalid part")?);
+            }
+            "--input" | "-i" => {
+                let value = args.next().ok_or("Missing value for --input")?;
+                source = Some(Source::new(value));
+            }
+            "--json" => output = Output::Json,
+            "--progress" => progress = true,
+            "--trace" => trace = true,
+            "--trace-file" => {
+                let value = args.next().ok_or("Missing value for --trace-file")?;
+                trace_file = Some(value);
+                trace = true;
+            }
+            "--steps" => {
+                let value = args.next().ok_or("Missing value for --steps")?;
+                steps = Some(step_range(value).ok_or("Invalid range of steps")?);
+            }
+            value if day.is_none() => day = Some(value.parse().map_err(|_| "Invalid day")?),
+            value => return Err(format!("Unexpected argument {}", value).into()),
+        }
+    }
+
+    let day: u8 = day.ok_or("Missing day")?;
+    let puzzle = puzzle(day).ok_or(format!("No solution for day {}", day))?;
+
+    let parts = match part {
+        Some(part) if !puzzle.parts().contains(&part) => {
+            return Err(format!("Day {} has no part {}", day, part).into())
+        }
+        Some(part) => vec![part],
+        None => puzzle.parts(),
+    };
+
+    let mut observer: Box<dyn Observer> = match (progress, trace) {
+        (true, true) => return Err("Choose either --progress or --trace".into()),
+        (true, false) => Box::new(Progress::new(format!("Day {:02}", day))),
+        (false, true) => {
+            let out: Box<dyn Write> = match trace_file {
+                Some(path) => Box::new(BufWriter::new(
+                    File::create(path).map_err(|e| format!("{}: {}", path, e))?,
+                )),
+                None => Box::new(io::stderr()),
+            };
+            Box::new(Trace::new(out).steps(steps.unwrap_or(1..=usize::MAX)))
+        }
+        (false, false) if steps.is_some() => return Err("--steps needs --trace".into()),
+        (false, false) => Box::new(Silent),
+    };
+
+    solve(puzzle, &parts, source.as_ref(), output, observer.as_mut())
+}
+
+fn all(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let output = match args {
+        [] => Output::Text,
+        [flag] if flag == "--json" => Output::Json,
+        _ => return Err(format!("Unexpected argument {}", args[0]).into()),
+    };
+
+    for puzzle in PUZZLES.iter() {
+        solve(puzzle, &puzzle.parts(), None, output, &mut Silent)?;
+    }
+
+    Ok(())
+}
+
+fn verify(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let path = match args {
+        [path] => Path::new(path),
+        _ => return Err("Expected the answers file".into()),
+    };
+    let expected = Answers::read(path)?;
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+
+    for puzzle in PUZZLES.iter() {
+        let parts = puzzle.parts();
+        let answers = puzzle
+            .read_input(None)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|input| puzzle.solve(&input, &parts));
+
+        let answers = match answers {
+            Ok(answers) => answers,
+            Err(e) => {
+                println!("Day {:02}: ERROR {}", puzzle.day, e);
+                failed += parts.len();
+                continue;
+            }
+        };
+
+        for (&part, answer) in parts.iter().zip(answers) {
+            print!("Day {:02} part {}: ", puzzle.day, part);
+            match expected.check(puzzle.day, part, &answer) {
+                Verdict::Pass => {
+                    passed += 1;
+                    println!("PASS {}", answer);
+                }
+                Verdict::Fail(expected) => {
+                    failed += 1;
+                    println!("FAIL expected {}, got {}", expected, answer);
+                }
+                Verdict::Missing => {
+                    missing += 1;
+                    println!("MISSING {}", answer);
+                }
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+
+    if failed > 0 {
+        return Err(format!("{} answers do not match {}", failed, path.display()).into());
+    }
+
+    Ok(())
+}
+
+fn record(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let path = match args {
+        [path] => Path::new(path),
+        _ => return Err("Expected the answers file".into()),
+    };
+
+    let mut answers = Answers::new();
+    for puzzle in PUZZLES.iter() {
+        let parts = puzzle.parts();
+        let solved = puzzle
+            .read_input(None)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|input| puzzle.solve(&input, &parts));
+
+        match solved {
+            Ok(solved) => {
+                for (&part, answer) in parts.iter().zip(solved) {
+                    answers.insert(puzzle.day, part, answer);
+                }
+            }
+            Err(e) => eprintln!("Day {:02}: skipped, {}", puzzle.day, e),
+        }
+    }
+
+    answers.write(path)
+}
+
+fn bench(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut days = vec![];
+    let mut runs = 10;
+    let mut json = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" | "-n" => {
+                let value = args.next().ok_or("Missing value for --runs")?;
+                runs = value.parse().map_err(|_| "Invalid number of runs")?;
+            }
+            "--json" => json = true,
+            value => {
+                let day: u8 = value.parse().map_err(|_| "Invalid day")?;
+                days.push(puzzle(day).ok_or(format!("No solution for day {}", day))?);
+            }
+        }
+    }
+
+    if days.is_empty() {
+        days = PUZZLES.iter().collect();
+    }
+
+    let mut benchmarks = vec![];
+    for puzzle in days {
+        let input = puzzle.read_input(None)?;
+        let benchmark =
+            bench::run(puzzle, &input, runs).map_err(|e| format!("Day {}: {}", puzzle.day, e))?;
+        benchmarks.push(benchmark);
+    }
+
+    if json {
+        println!("{}", bench::json(&benchmarks));
+    } else {
+        print!("{}", bench::table(&benchmarks));
+    }
+
+    Ok(())
+}
+
+fn generate(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut day = None;
+    let mut seed = 0;
+    let mut size = 10;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().ok_or("Missing value for --seed")?;
+                seed = value.parse().map_err(|_| "Invalid seed")?;
+            }
+            "--size" => {
+                let value = args.next().ok_or("Missing value for --size")?;
+                size = value.parse().map_err(|_| "Invalid size")?;
+            }
+            value if day.is_none() => day = Some(value.parse().map_err(|_| "Invalid day")?),
+            value => return Err(format!("Unexpected argument {}", value).into()),
+        }
+    }
+
+    let day: u8 = day.ok_or("Missing day")?;
+    let puzzle = puzzle(day).ok_or(format!("No solution for day {}", day))?;
+    print!("{}", puzzle.generate(seed, size));
+
+    Ok(())
+}
+
+fn compare(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut day = None;
+    let mut seed = 0;
+    let mut cases = 1000;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().ok_or("Missing value for --seed")?;
+                seed = value.parse().map_err(|_| "Invalid seed")?;
+            }
+            "--cases" => {
+                let value = args.next().ok_or("Missing value for --cases")?;
+                cases = value.parse().map_err(|_| "Invalid number of cases")?;
+            }
+            value if day.is_none() => day = Some(value.parse().map_err(|_| "Invalid day")?),
+            value => return Err(format!("Unexpected argument {}", value).into()),
+        }
+    }
+
+    let day: u8 = day.ok_or("Missing day")?;
+    let pair = differential::pair(day).ok_or(format!("Day {} has one implementation", day))?;
+    let (left, right) = pair.names;
+
+    match pair.compare(seed, cases) {
+        None => {
+            println!(
+                "Day {:02}: {} and {} agree on {} inputs",
+                day, left, right, cases
+            );
+            Ok(())
+        }
+        Some(disagreement) => Err(format!(
+            "Day {:02}: {} and {} disagree, {}",
+            day, left, right, disagreement
+        )
+        .into()),
+    }
+}
+
+fn fetch(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut days = vec![];
+    let mut fetcher = Fetcher::from_env();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--session" => {
+                let value = args.next().ok_or("Missing value for --session")?;
+                fetcher = fetcher.with_session(value);
+            }
+            "--base-url" => {
+                let value = args.next().ok_or("Missing value for --base-url")?;
+                fetcher = fetcher.with_base_url(value);
+            }
+            value => {
+                let day: u8 = value.parse().map_err(|_| "Invalid day")?;
+                days.push(
+                    puzzle(day)
+                        .ok_or(format!("No solution for day {}", day))?
+                        .day,
+                );
+            }
+        }
+    }
+
+    if days.is_empty() {
+        days = PUZZLES.iter().map(|p| p.day).collect();
+    }
+
+    for day in days {
+        let cached = fetcher.is_cached(day);
+        let path = fetcher.fetch(day)?;
+        let status = if cached { "cached" } else { "downloaded" };
+        println!("Day {:02}: {} ({})", day, path.display(), status);
+    }
+
+    Ok(())
+}
+
+fn audit(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut policies = vec![];
+    let mut source = None;
+    let mut report = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--policy" => {
+                let value = args.next().ok_or("Missing value for --policy")?;
+                policies.push(day02::policy(value)?);
+            }
+            "--input" | "-i" => {
+                let value = args.next().ok_or("Missing value for --input")?;
+                source = Some(Source::new(value));
+            }
+            "--report" => report = report.or(Some(day02::Format::Text)),
+            "--csv" => report = Some(day02::Format::Csv),
+            value => return Err(format!("Unexpected argument {}", value).into()),
+        }
+    }
+
+    if policies.is_empty() {
+        policies = vec![Box::new(day02::CountRange), Box::new(day02::PositionXor)];
+    }
+
+    let puzzle = puzzle(2).ok_or("No solution for day 2")?;
+    let text = input::normalize(&puzzle.read_input(source.as_ref())?);
+
+    if let Some(format) = report {
+        print!("{}", day02::report(&day02::audit(&text), &policies, format));
+        return Ok(());
+    }
+
+    let entries = day02::parse(&text).map_err(|e| format!("Day 2: {}", e))?;
+    for policy in &policies {
+        println!(
+            "{}: {} of {} valid",
+            policy.name(),
+            day02::count_valid(&entries, policy.as_ref()),
+            entries.len()
+        );
+    }
+
+    Ok(())
+}
+
+fn toboggan(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut slopes = vec![];
+    let mut search = None;
+    let mut draw = None;
+    let mut source = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--search" => {
+                let value = args.next().ok_or("Missing value for --search")?;
+                search = Some(value.parse().map_err(|_| "Invalid value for --search")?);
+            }
+            "--draw" => {
+                let value = args.next().ok_or("Missing value for --draw")?;
+                draw = Some(value.parse::<day03::Slope>()?);
+            }
+            "--input" | "-i" => {
+                let value = args.next().ok_or("Missing value for --input")?;
+                source = Some(Source::new(value));
+            }
+            value => slopes.push(value.parse::<day03::Slope>()?),
+        }
+    }
+
+    let puzzle = puzzle(3).ok_or("No solution for day 3")?;
+    let text = input::normalize(&puzzle.read_input(source.as_ref())?);
+    let forest = day03::parse(&text).map_err(|e| format!("Day 3: {}", e))?;
+
+    if let Some(slope) = draw {
+        print!("{}", forest.draw(slope));
+        return Ok(());
+    }
+
+    if let Some(bound) = search {
+        if bound == 0 {
+            return Err("Invalid value for --search".into());
+        }
+        let (fewest, most) = forest.extremes(bound);
+        println!("fewest: {}, {} trees", fewest.slope, fewest.trees);
+        println!("most: {}, {} trees", most.slope, most.trees);
+        return Ok(());
+    }
+
+    if slopes.is_empty() {
+        slopes = day03::SLOPES.to_vec();
+    }
+
+    let mut product = Some(1_usize);
+    for &slope in &slopes {
+        let trees = forest.trees(slope);
+        product = product.and_then(|product| product.checked_mul(trees));
+        println!("{}: {} trees", slope, trees);
+    }
+    match product {
+        Some(product) => println!("product: {}", product),
+        None => return Err("The product of the trees overflows".into()),
+    }
+
+    Ok(())
+}
+
+fn repl(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut day = None;
+    let mut source = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => {
+                let value = args.next().ok_or("Missing value for --input")?;
+                source = Some(Source::new(value));
+            }
+            value if day.is_none() => day = Some(value.parse().map_err(|_| "Invalid day")?),
+            value => return Err(format!("Unexpected argument {}", value).into()),
+        }
+    }
+
+    let mut repl = Repl::new();
+    match (day, source) {
+        (Some(day), source) => {
+            let puzzle = puzzle(day).ok_or(format!("No solution for day {}", day))?;
+            let input = puzzle.read_input(source.as_ref())?;
+            println!("{}", repl.load(day, &input)?);
+        }
+        (None, Some(_)) => return Err("--input needs a day".into()),
+        (None, None) => {}
+    }
+
+    repl.run(io::stdin().lock(), io::stdout())?;
+    Ok(())
+}
+
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("list") => {
+            list();
+            Ok(())
+        }
+        Some("run") => run(&args[1..]),
+        Some("all") => all(&args[1..]),
+        Some("verify") => verify(&args[1..]),
+        Some("record") => record(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("generate") => generate(&args[1..]),
+        Some("compare") => compare(&args[1..]),
+        Some("fetch") => fetch(&args[1..]),
+        Some("audit") => audit(&args[1..]),
+        Some("toboggan") => toboggan(&args[1..]),
+        Some("repl") => repl(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        _ => {
+            eprint!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,191 @@
+//! Number theory modulo an integer: powers, inverses, simultaneous congruences and discrete
+//! logarithms
+//!
+//! The products of `u64` residues are computed in `u128`, the congruences in `i128`, so no
+//! intermediate result overflows.
+
+use std::collections::HashMap;
+
+/// `a * b mod m`
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// `base^exp mod m` by repeated squaring
+pub fn pow_mod(base: u64, exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    let mut exp = exp;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// `a * b mod m` for non-negative `a` and `b` below `m`, without overflowing for `m` below `2^126`
+fn mul_mod_i128(a: i128, b: i128, m: i128) -> i128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    // double and add
+    let (mut a, mut b, mut result) = (a, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = (result + a) % m;
+        }
+        a = (a + a) % m;
+        b >>= 1;
+    }
+    result
+}
+
+/// The greatest common divisor `g` of `a` and `b` with the coefficients `x` and `y` such that
+/// `a * x + b * y = g`
+pub fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime
+pub fn mod_inv(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = egcd(a.rem_euclid(m), m);
+    if g == 1 {
+        Some(x.rem_euclid(m))
+    } else {
+        None
+    }
+}
+
+/// Solve the congruences `x ≡ residue (mod modulus)` by the Chinese remainder theorem.  The
+/// moduli need not be coprime.  Returns the smallest non-negative solution and the modulus of
+/// all solutions, their least common multiple, or `None` if the congruences contradict each
+/// other or the modulus doesn't fit in an `i128`.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut x = 0;
+    let mut modulus = 1;
+
+    for &(residue, m) in congruences {
+        assert!(m > 0, "modulus {} is not positive", m);
+
+        // x + modulus * k ≡ residue (mod m)
+        let (g, inv, _) = egcd(modulus, m);
+        let diff = (residue - x).rem_euclid(m);
+        if diff % g != 0 {
+            return None;
+        }
+
+        let step = m / g;
+        let k = mul_mod_i128(diff / g % step, inv.rem_euclid(step), step);
+        // x < modulus and k < step, so x + modulus * k < modulus * step
+        let lcm = modulus.checked_mul(step)?;
+        x += modulus * k;
+        modulus = lcm;
+    }
+
+    Some((x, modulus))
+}
+
+/// The smallest `x` with `base^x ≡ target (mod m)` by the baby-step giant-step algorithm, in
+/// `O(sqrt(m))` time and memory.  `base` and `m` must be coprime.
+pub fn discrete_log(base: u64, target: u64, m: u64) -> Option<u64> {
+    let target = target % m;
+    if 1 % m == target {
+        return Some(0);
+    }
+
+    // baby steps: target * base^j for j in 0..n, keeping the largest j of every value
+    let n = (m as f64).sqrt().ceil() as u64;
+    let mut baby = HashMap::new();
+    let mut value = target;
+    for j in 0..n {
+        baby.insert(value, j);
+        value = mul_mod(value, base, m);
+    }
+
+    // giant steps: base^(i * n) = target * base^j, so x = i * n - j
+    let giant = pow_mod(base, n, m);
+    let mut value = 1;
+    for i in 1..=n {
+        value = mul_mod(value, giant, m);
+        if let Some(&j) = baby.get(&value) {
+            return Some(i * n - j);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raises_powers() {
+        assert_eq!(pow_mod(7, 8, 20201227), 5764801);
+        assert_eq!(pow_mod(17807724, 8, 20201227), 14897079);
+        assert_eq!(pow_mod(2, 0, 1), 0);
+        assert_eq!(pow_mod(u64::MAX, 2, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn inverts() {
+        assert_eq!(egcd(240, 46), (2, -9, 47));
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(-3, 11), Some(7));
+        assert_eq!(mod_inv(6, 9), None);
+    }
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(0, 4), (1, 6)]), None);
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+
+        let big = 1_000_000_000_000_000_003;
+        assert_eq!(
+            crt(&[(1, big), (2, 1_000_000_007)]),
+            Some((788_461_544_000_000_002_365_384_633, big * 1_000_000_007))
+        );
+        let (a, b) = ((1 << 31) - 1, (1 << 89) - 1);
+        assert_eq!(
+            crt(&[(5, a), (7, b)]),
+            Some((354_460_800_031_388_269_440_703_472_074_612_195, a * b))
+        );
+        assert_eq!(crt(&[(0, big), (0, big + 2), (0, big + 4)]), None);
+    }
+
+    #[test]
+    fn finds_logarithms() {
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(3, 1, 7), Some(0));
+        assert_eq!(discrete_log(2, 3, 7), None);
+
+        for x in 0..100 {
+            assert_eq!(discrete_log(3, pow_mod(3, x, 101), 101), Some(x));
+        }
+    }
+}
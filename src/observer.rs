@@ -0,0 +1,217 @@
+//! Progress reports of the iterative solvers
+//!
+//! The solvers which simulate a process step by step report every step to an `Observer`: the
+//! step number, the population (occupied seats, active cubes, black tiles, ...) and the state
+//! after the step.  The state is formatted only if the observer prints it.
+//!
+//! Some solvers also report events, what happens during a step in more detail than the state
+//! after it: the cards drawn in a round of Combat, the cups moved by the crab, the seats taken
+//! and left.  The events of a step are reported before the step.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+pub trait Observer {
+    /// The solver starts solving the given part
+    fn part(&mut self, _part: u8) {}
+
+    /// The solver is done with the current part
+    fn done(&mut self) {}
+
+    /// The solver is done with a step, numbered from 1
+    fn step(&mut self, step: usize, population: usize, state: &dyn fmt::Display);
+
+    /// Something happened during the given step
+    fn event(&mut self, _step: usize, _event: &dyn fmt::Display) {}
+}
+
+impl<F: FnMut(usize, usize, &dyn fmt::Display)> Observer for F {
+    fn step(&mut self, step: usize, population: usize, state: &dyn fmt::Display) {
+        self(step, population, state)
+    }
+}
+
+/// Ignore the progress
+pub struct Silent;
+
+impl Observer for Silent {
+    fn step(&mut self, _step: usize, _population: usize, _state: &dyn fmt::Display) {}
+}
+
+/// How often the progress line is updated
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A progress line on the standard error, updated in place
+pub struct Progress {
+    label: String,
+    part: u8,
+    last: Option<(usize, usize)>,
+    updated: Option<Instant>,
+}
+
+impl Progress {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            part: 0,
+            last: None,
+            updated: None,
+        }
+    }
+
+    fn print(&self) {
+        if let Some((step, population)) = self.last {
+            eprint!(
+                "\r{} part {}: step {}, population {}",
+                self.label, self.part, step, population
+            );
+            io::stderr().flush().ok();
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.last.is_some() {
+            self.print();
+            eprintln!();
+        }
+        self.last = None;
+        self.updated = None;
+    }
+}
+
+impl Observer for Progress {
+    fn part(&mut self, part: u8) {
+        self.finish();
+        self.part = part;
+    }
+
+    fn done(&mut self) {
+        self.finish();
+    }
+
+    fn step(&mut self, step: usize, population: usize, _state: &dyn fmt::Display) {
+        self.last = Some((step, population));
+        if self
+            .updated
+            .is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL)
+        {
+            self.print();
+            self.updated = Some(Instant::now());
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Every step with the full state, or with its events if the solver reports events
+pub struct Trace<W: Write> {
+    out: W,
+    steps: RangeInclusive<usize>,
+    /// Whether events were reported since the last step
+    events: bool,
+}
+
+impl<W: Write> Trace<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            steps: 1..=usize::MAX,
+            events: false,
+        }
+    }
+
+    /// Trace only the given steps
+    pub fn steps(self, steps: RangeInclusive<usize>) -> Self {
+        Self { steps, ..self }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Observer for Trace<W> {
+    fn part(&mut self, part: u8) {
+        writeln!(self.out, "== Part {} ==", part).ok();
+    }
+
+    fn step(&mut self, step: usize, population: usize, state: &dyn fmt::Display) {
+        if self.steps.contains(&step) && !self.events {
+            writeln!(self.out, "Step {}: population {}", step, population).ok();
+            writeln!(self.out, "{}", state).ok();
+        }
+        self.events = false;
+    }
+
+    fn event(&mut self, step: usize, event: &dyn fmt::Display) {
+        if self.steps.contains(&step) {
+            writeln!(self.out, "{}", event).ok();
+        }
+        self.events = true;
+    }
+}
+
+/// A range of steps such as `5`, `10..20`, `10..` or `..20`, both ends included
+pub fn step_range(text: &str) -> Option<RangeInclusive<usize>> {
+    let bound = |text: &str, default| match text {
+        "" => Some(default),
+        _ => text.parse().ok(),
+    };
+
+    match text.split_once("..") {
+        Some((from, to)) => Some(bound(from, 1)?..=bound(to, usize::MAX)?),
+        None => text.parse().ok().map(|step| step..=step),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observes_with_closures() {
+        let mut steps = vec![];
+        let mut observer = |step, population, _: &dyn fmt::Display| steps.push((step, population));
+        observer.part(1);
+        observer.step(1, 10, &"");
+        observer.step(2, 7, &"");
+        assert_eq!(steps, [(1, 10), (2, 7)]);
+    }
+
+    #[test]
+    fn traces_states() {
+        let mut trace = Trace::new(vec![]);
+        trace.part(2);
+        trace.step(1, 2, &"#.#");
+        assert_eq!(
+            String::from_utf8(trace.into_inner()).unwrap(),
+            "== Part 2 ==\nStep 1: population 2\n#.#\n"
+        );
+    }
+
+    #[test]
+    fn traces_events() {
+        let mut trace = Trace::new(vec![]).steps(2..=3);
+        for step in 1..=4 {
+            trace.event(step, &format!("event {}", step));
+            trace.step(step, 0, &"state");
+        }
+        trace.step(5, 0, &"state");
+        assert_eq!(
+            String::from_utf8(trace.into_inner()).unwrap(),
+            "event 2\nevent 3\n"
+        );
+
+        assert_eq!(step_range("5"), Some(5..=5));
+        assert_eq!(step_range("10..20"), Some(10..=20));
+        assert_eq!(step_range("10.."), Some(10..=usize::MAX));
+        assert_eq!(step_range("..20"), Some(1..=20));
+        assert_eq!(step_range("1..x"), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! A small seeded pseudo-random number generator for the input generators
+//!
+//! The same seed gives the same sequence on every platform, so a generated input can be
+//! reproduced from its seed.  Good enough for test inputs, not for cryptography.
+
+/// The SplitMix64 generator
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "empty range");
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// A number in `low..=high`
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "empty range {}..={}", low, high);
+        let span = (high as i128 - low as i128 + 1) as u128;
+        let offset = (self.next_u64() as u128 * span) >> 64;
+        (low as i128 + offset as i128) as i64
+    }
+
+    /// True with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// Put the items in a random order
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// `k` distinct items in a random order
+    pub fn sample<T: Clone>(&mut self, items: &[T], k: usize) -> Vec<T> {
+        let mut items = items.to_vec();
+        self.shuffle(&mut items);
+        items.truncate(k);
+        items
+    }
+
+    /// A word of `len` letters from `alphabet`
+    pub fn word(&mut self, alphabet: &str, len: usize) -> String {
+        let letters: Vec<char> = alphabet.chars().collect();
+        (0..len).map(|_| *self.choose(&letters)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeats_sequences() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+        assert_eq!(Rng::new(0).next_u64(), 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn stays_in_range() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!(rng.below(3) < 3);
+            assert!((-2..=2).contains(&rng.between(-2, 2)));
+        }
+        assert_eq!(rng.between(i64::MIN, i64::MIN), i64::MIN);
+        assert!(rng.between(i64::MIN, i64::MAX) != rng.between(i64::MIN, i64::MAX));
+
+        let mut seen = [false; 5];
+        for _ in 0..100 {
+            seen[rng.between(0, 4) as usize] = true;
+        }
+        assert_eq!(seen, [true; 5]);
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+    }
+
+    #[test]
+    fn shuffles() {
+        let mut rng = Rng::new(2);
+        let mut items: Vec<_> = (0..10).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..10).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+
+        let sample = rng.sample(&items, 3);
+        assert_eq!(sample.len(), 3);
+        assert!(sample.iter().all(|x| items.contains(x)));
+        assert_eq!(rng.word("ab", 4).len(), 4);
+    }
+}
@@ -0,0 +1,336 @@
+//! An interactive shell to explore the parsed puzzle inputs
+//!
+//! The shell loads the input of one day at a time and answers queries about it.  Every day can
+//! be solved, and the iterative solvers can show their state after any step.  Some days answer
+//! their own queries over the parsed input, see `Solution::query`: the contents of a bag, the
+//! run of a program, the expansion of a rule, the neighbours of a tile, ...
+
+use crate::input::{normalize, Source};
+use crate::observer::{Observer, Silent};
+use crate::{puzzle, Answer, ParseError, Puzzle, Solution};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// The answer to a command, or why it failed
+pub type Reply = Result<String, String>;
+
+const HELP: &str = "\
+Commands:
+    day <n> [<file>]         Load the input of a day, from the input directory
+                             unless a file is given
+    solve [<part>]           Solve the day, optionally only one part
+    step <n> [<part>]        The events of step <n> of an iterative solver and
+                             the state after it, in part 1 unless given
+    help                     Show the commands and the queries of the day
+    quit                     Leave the shell, as does the end of the input";
+
+/// A parsed puzzle input
+pub trait Explorer {
+    /// Solve the given part, `None` if the puzzle doesn't have it, an error if the input has no
+    /// answer
+    fn solve(&self, part: u8, observer: &mut dyn Observer) -> Option<Result<Answer, String>>;
+
+    /// Answer a query of the day, `None` if there is no such query
+    fn query(&self, query: &str, args: &[&str]) -> Option<Reply>;
+}
+
+struct Parsed<S: Solution> {
+    input: S::Input,
+    solution: PhantomData<S>,
+}
+
+impl<S: Solution> Explorer for Parsed<S> {
+    fn solve(&self, part: u8, observer: &mut dyn Observer) -> Option<Result<Answer, String>> {
+        S::solve_with(&self.input, part, observer)
+    }
+
+    fn query(&self, query: &str, args: &[&str]) -> Option<Reply> {
+        S::query(&self.input, query, args)
+    }
+}
+
+/// Parse the puzzle input of `S` for the shell, normalised first like for solving
+pub(crate) fn explore<S>(input: &str) -> Result<Box<dyn Explorer>, ParseError>
+where
+    S: Solution + 'static,
+    S::Input: 'static,
+{
+    let input = S::parse(&normalize(input))?;
+    Ok(Box::new(Parsed::<S> {
+        input,
+        solution: PhantomData,
+    }))
+}
+
+/// The argument `i` of a query, `name` tells what it is when it is missing or invalid
+pub fn arg<T: FromStr>(args: &[&str], i: usize, name: &str) -> Result<T, String> {
+    let arg = args.get(i).ok_or_else(|| format!("missing {}", name))?;
+    arg.parse()
+        .map_err(|_| format!("invalid {} \"{}\"", name, arg))
+}
+
+/// The events of one step and the state after it
+#[derive(Default)]
+struct Snapshot {
+    step: usize,
+    events: Vec<String>,
+    state: Option<String>,
+}
+
+impl Observer for Snapshot {
+    fn step(&mut self, step: usize, _population: usize, state: &dyn fmt::Display) {
+        if step == self.step {
+            self.state = Some(state.to_string());
+        }
+    }
+
+    fn event(&mut self, step: usize, event: &dyn fmt::Display) {
+        if step == self.step {
+            self.events.push(event.to_string());
+        }
+    }
+}
+
+/// The shell with the input of the current day
+#[derive(Default)]
+pub struct Repl {
+    loaded: Option<(&'static Puzzle, Box<dyn Explorer>)>,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the input of the given day and make it the current day
+    pub fn load(&mut self, day: u8, input: &str) -> Reply {
+        let puzzle = puzzle(day).ok_or_else(|| format!("No solution for day {}", day))?;
+        let parsed = puzzle
+            .explore(input)
+            .map_err(|e| format!("Day {}: {}", day, e))?;
+        self.loaded = Some((puzzle, parsed));
+        Ok(format!("Day {:02}: {}", puzzle.day, puzzle.title))
+    }
+
+    pub fn prompt(&self) -> String {
+        match &self.loaded {
+            Some((puzzle, _)) => format!("day{:02}> ", puzzle.day),
+            None => "aoc2020> ".to_string(),
+        }
+    }
+
+    /// Execute one line of input, `None` if it asks to leave the shell
+    pub fn execute(&mut self, line: &str) -> Option<Reply> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Some(Ok(String::new())),
+        };
+
+        Some(match command {
+            "quit" | "exit" => return None,
+            "help" => Ok(self.help()),
+            "day" => self.day(args),
+            command => match &self.loaded {
+                Some((puzzle, parsed)) => match command {
+                    "solve" => solve(puzzle, parsed.as_ref(), args),
+                    "step" => step(puzzle, parsed.as_ref(), args),
+                    query => parsed.query(query, args).unwrap_or_else(|| {
+                        Err(format!(
+                            "Unknown command {}, type help for the commands",
+                            query
+                        ))
+                    }),
+                },
+                None => Err("No day loaded, type day <n> first".to_string()),
+            },
+        })
+    }
+
+    fn help(&self) -> String {
+        let mut help = HELP.to_string();
+        if let Some((puzzle, _)) = &self.loaded {
+            if !puzzle.queries.is_empty() {
+                help += &format!("\n\nQueries of day {}:", puzzle.day);
+                for (usage, description) in puzzle.queries {
+                    help += &format!("\n    {:<24} {}", usage, description);
+                }
+            }
+        }
+        help
+    }
+
+    fn day(&mut self, args: &[&str]) -> Reply {
+        let day = arg(args, 0, "day")?;
+        let puzzle = puzzle(day).ok_or_else(|| format!("No solution for day {}", day))?;
+        let source = args.get(1).map(|path| Source::new(path));
+        if args.len() > 2 {
+            return Err(format!("Unexpected argument {}", args[2]));
+        }
+
+        let input = puzzle
+            .read_input(source.as_ref())
+            .map_err(|e| e.to_string())?;
+        self.load(day, &input)
+    }
+
+    /// Read commands from `input` until the end or `quit`, writing the prompts and the replies
+    /// to `out`
+    pub fn run(&mut self, input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+        let mut lines = input.lines();
+        loop {
+            write!(out, "{}", self.prompt())?;
+            out.flush()?;
+
+            let line = match lines.next() {
+                Some(line) => line?,
+                None => return writeln!(out),
+            };
+            match self.execute(&line) {
+                None => return Ok(()),
+                Some(Ok(reply)) if reply.is_empty() => {}
+                Some(Ok(reply)) => writeln!(out, "{}", reply.trim_end())?,
+                Some(Err(e)) => writeln!(out, "Error: {}", e)?,
+            }
+        }
+    }
+}
+
+/// The parts given by the arguments, all of them if there are none
+fn parts(puzzle: &Puzzle, args: &[&str]) -> Result<Vec<u8>, String> {
+    match args.first() {
+        None => Ok(puzzle.parts()),
+        Some(_) => {
+            let part = arg(args, 0, "part")?;
+            if puzzle.parts().contains(&part) {
+                Ok(vec![part])
+            } else {
+                Err(format!("Day {} has no part {}", puzzle.day, part))
+            }
+        }
+    }
+}
+
+fn solve(puzzle: &Puzzle, parsed: &dyn Explorer, args: &[&str]) -> Reply {
+    let answers: Vec<String> = parts(puzzle, args)?
+        .into_iter()
+        .filter_map(|part| {
+            Some(match parsed.solve(part, &mut Silent)? {
+                Ok(answer) => format!("Part {} {}", part, answer),
+                Err(e) => format!("Part {}: {}", part, e),
+            })
+        })
+        .collect();
+    Ok(answers.join("\n"))
+}
+
+fn step(puzzle: &Puzzle, parsed: &dyn Explorer, args: &[&str]) -> Reply {
+    let step = arg(args, 0, "step")?;
+    let part = match parts(puzzle, &args[1..])?[..] {
+        [part] => part,
+        _ => 1,
+    };
+
+    let mut snapshot = Snapshot {
+        step,
+        ..Snapshot::default()
+    };
+    parsed.solve(part, &mut snapshot);
+
+    match (snapshot.events.is_empty(), snapshot.state) {
+        (true, None) => Err(format!("Part {} has no step {}", part, step)),
+        (_, state) => {
+            let mut reply = snapshot.events.join("\n");
+            if let Some(state) = state {
+                if !reply.is_empty() {
+                    reply += "\n\n";
+                }
+                reply += &state;
+            }
+            Ok(reply)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEATS: &str = "\
+L.LL.LL.LL
+LLLLLLL.LL
+L.L.L..L..
+LLLL.LL.LL
+L.LL.LL.LL
+L.LLLLL.LL
+..L.L.....
+LLLLLLLLLL
+L.LLLLLL.L
+L.LLLLL.LL
+";
+
+    fn reply(repl: &mut Repl, line: &str) -> Reply {
+        repl.execute(line).expect("the shell quit")
+    }
+
+    #[test]
+    fn loads_days() {
+        let mut repl = Repl::new();
+        assert_eq!(repl.prompt(), "aoc2020> ");
+        assert_eq!(
+            reply(&mut repl, "solve"),
+            Err("No day loaded, type day <n> first".to_string())
+        );
+
+        assert_eq!(
+            repl.load(11, SEATS),
+            Ok("Day 11: Seating System".to_string())
+        );
+        assert_eq!(repl.prompt(), "day11> ");
+        assert_eq!(
+            reply(&mut repl, "solve"),
+            Ok("Part 1 37\nPart 2 26".to_string())
+        );
+        assert_eq!(reply(&mut repl, "solve 2"), Ok("Part 2 26".to_string()));
+        assert_eq!(
+            reply(&mut repl, "solve 3"),
+            Err("Day 11 has no part 3".to_string())
+        );
+        assert_eq!(reply(&mut repl, ""), Ok(String::new()));
+        assert!(reply(&mut repl, "frobnicate").is_err());
+        assert!(repl.load(1, "x\n").is_err());
+        assert_eq!(repl.prompt(), "day11> ");
+        assert_eq!(repl.execute("quit"), None);
+    }
+
+    #[test]
+    fn steps_simulations() {
+        let mut repl = Repl::new();
+        repl.load(11, SEATS).unwrap();
+
+        let step = reply(&mut repl, "step 1").unwrap();
+        assert!(step.starts_with("-- Round 1 --\ntaken 71 seats: (0, 0), (2, 0)"));
+        assert!(step.contains(" (9, 9)\nleft 0 seats\n\n#.##.##.##\n#######.##\n"));
+        assert_eq!(
+            reply(&mut repl, "step 99"),
+            Err("Part 1 has no step 99".to_string())
+        );
+        assert_eq!(
+            reply(&mut repl, "step x"),
+            Err("invalid step \"x\"".to_string())
+        );
+    }
+
+    #[test]
+    fn runs_sessions() {
+        let mut repl = Repl::new();
+        let mut out = vec![];
+        repl.run("day 0\nhelp\n".as_bytes(), &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("aoc2020> Error: No solution for day 0\naoc2020> Commands:\n"));
+        assert!(out.ends_with("end of the input\naoc2020> \n"));
+    }
+}
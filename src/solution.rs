@@ -0,0 +1,115 @@
+use crate::observer::{Observer, Silent};
+use crate::random::Rng;
+use crate::repl::Reply;
+use crate::ParseError;
+use std::fmt;
+
+/// The answer to one part of a puzzle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Integer(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+integer_answer!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// The solution of a puzzle: the parser of the puzzle input and the solvers of its parts
+pub trait Solution {
+    /// The parsed puzzle input shared by the two parts
+    type Input;
+
+    /// Number of parts of the puzzle.  The last day has only one.
+    const PARTS: u8 = 2;
+
+    /// The usage and description of the queries answered by `query`
+    const QUERIES: &'static [(&'static str, &'static str)] = &[];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Solve part 1, or tell why the input has no answer
+    fn part1(input: &Self::Input) -> Result<Answer, String>;
+
+    /// Solve part 2, or tell why the input has no answer
+    fn part2(input: &Self::Input) -> Result<Answer, String>;
+
+    /// A random input accepted by the parser and solvable by every part, growing with `size`
+    fn generate(rng: &mut Rng, size: usize) -> String;
+
+    /// Solve the given part, `None` if the puzzle doesn't have it, an error if the input has no
+    /// answer
+    fn solve(input: &Self::Input, part: u8) -> Option<Result<Answer, String>> {
+        Self::solve_with(input, part, &mut Silent)
+    }
+
+    /// Like `solve`, reporting every step to `observer`.  Only the iterative solvers override
+    /// it, the others ignore the observer.
+    fn solve_with(
+        input: &Self::Input,
+        part: u8,
+        _observer: &mut dyn Observer,
+    ) -> Option<Result<Answer, String>> {
+        match part {
+            1 => Some(Self::part1(input)),
+            2 if Self::PARTS >= 2 => Some(Self::part2(input)),
+            _ => None,
+        }
+    }
+
+    /// Answer a query of the shell about the parsed input, `None` if there is no such query.
+    /// `args` are the words following the name of the query.
+    fn query(_input: &Self::Input, _query: &str, _args: &[&str]) -> Option<Reply> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displays_answers() {
+        assert_eq!(Answer::from(42_usize).to_string(), "42");
+        assert_eq!(Answer::from(-7).to_string(), "-7");
+        assert_eq!(Answer::from("67384529").to_string(), "67384529");
+    }
+
+    #[test]
+    fn converts_integers() {
+        assert_eq!(Answer::from(u64::MAX), Answer::Integer(u64::MAX as i128));
+        assert_eq!(Answer::from(3_i32), Answer::from(3_usize));
+        assert_ne!(Answer::from(3_i32), Answer::from("3"));
+    }
+}